@@ -0,0 +1,252 @@
+"use strict";
+
+// The page renders the document with plain DOM calls, so it loads no
+// third-party scripts and works without internet access.
+(() => {
+  const methodColors = {
+    get: "blue",
+    post: "green",
+    put: "orange",
+    delete: "red",
+  };
+
+  function element(name, className, text) {
+    const result = document.createElement(name);
+
+    if (className) {
+      result.className = className;
+    }
+
+    if (text !== undefined) {
+      result.textContent = text;
+    }
+
+    return result;
+  }
+
+  function showError(error) {
+    document.querySelector("#message").textContent = error;
+    document.querySelector("#error").hidden = false;
+  }
+
+  function schemaName(ref) {
+    return ref.split("/").pop();
+  }
+
+  // Describes a schema as a JSON-like text with the referenced schemas
+  // inlined, `seen` stops recursion of self-referencing schemas.
+  function schemaText(document, schema, indent, seen) {
+    if (schema === undefined || schema === null) {
+      return "";
+    }
+
+    if (schema.$ref !== undefined) {
+      const name = schemaName(schema.$ref);
+
+      if (seen.includes(name)) {
+        return name;
+      }
+
+      return schemaText(
+        document,
+        document.components.schemas[name],
+        indent,
+        seen.concat([name]),
+      );
+    }
+
+    const variants = schema.oneOf || schema.anyOf;
+
+    if (variants !== undefined) {
+      return variants
+        .map((variant) => schemaText(document, variant, indent, seen))
+        .join(`\n${indent}| `);
+    }
+
+    const types = [].concat(schema.type || []);
+    const nullable = types.includes("null") ? " | null" : "";
+
+    if (types.includes("array")) {
+      const items = schemaText(document, schema.items, indent, seen);
+
+      return `[${items}]${nullable}`;
+    }
+
+    if (types.includes("object") || schema.properties !== undefined) {
+      const properties = schema.properties || {};
+      const required = schema.required || [];
+      const inner = `${indent}  `;
+      const lines = Object.keys(properties).map((key) => {
+        const optional = required.includes(key) ? "" : "?";
+        const value = schemaText(document, properties[key], inner, seen);
+
+        return `${inner}${key}${optional}: ${value}`;
+      });
+
+      if (lines.length === 0) {
+        return `object${nullable}`;
+      }
+
+      return `{\n${lines.join(",\n")}\n${indent}}${nullable}`;
+    }
+
+    if (schema.enum !== undefined) {
+      return schema.enum.map((value) => JSON.stringify(value)).join(" | ");
+    }
+
+    return types.join(" | ") || "any";
+  }
+
+  function contentText(document, content) {
+    if (content === undefined) {
+      return "";
+    }
+
+    const media = content["application/json"] || content["text/plain"];
+
+    return media === undefined
+      ? ""
+      : schemaText(document, media.schema, "", []);
+  }
+
+  function operationItem(document, path, method, operation) {
+    const params = (operation.parameters || []).map((param) => {
+      return {
+        name: param.name,
+        in: param.in,
+        type: schemaText(document, param.schema, "", []),
+        description: param.description || "",
+      };
+    });
+    const body = operation.requestBody || {};
+    const reply = (operation.responses || {})["200"] || {};
+
+    return {
+      path,
+      method,
+      summary: operation.summary || "",
+      params,
+      body: contentText(document, body.content),
+      reply: contentText(document, reply.content),
+    };
+  }
+
+  function schemaBlock(title, text) {
+    const block = element("div");
+    block.append(element("div", "ui tiny header", title));
+    block.append(element("pre", "", text));
+
+    return block;
+  }
+
+  function operationSegment(operation) {
+    const segment = element("div", "ui segment");
+    const summary = element("div");
+    const label = element(
+      "span",
+      `ui label ${methodColors[operation.method] || ""}`,
+      operation.method.toUpperCase(),
+    );
+
+    summary.append(label, " ", element("code", "", operation.path));
+    summary.append(` ${operation.summary}`);
+    segment.append(summary);
+
+    if (operation.params.length > 0) {
+      const table = element("table", "ui very basic compact table");
+      const body = element("tbody");
+
+      for (const param of operation.params) {
+        const row = element("tr");
+        const name = element("td");
+        name.append(element("code", "", param.name));
+        row.append(
+          name,
+          element("td", "", param.in),
+          element("td", "", param.type),
+          element("td", "", param.description),
+        );
+        body.append(row);
+      }
+
+      table.append(body);
+      segment.append(table);
+    }
+
+    if (operation.body) {
+      segment.append(schemaBlock("Request", operation.body));
+    }
+
+    if (operation.reply) {
+      segment.append(schemaBlock("Reply", operation.reply));
+    }
+
+    return segment;
+  }
+
+  function tagSection(tag) {
+    const section = element("div");
+    const header = element("h3", "ui dividing header", tag.name);
+    header.append(element("div", "sub header", tag.description));
+    section.append(header);
+
+    for (const operation of tag.operations) {
+      section.append(operationSegment(operation));
+    }
+
+    return section;
+  }
+
+  function documentShow(openapi) {
+    const tags = (openapi.tags || []).map((tag) => {
+      return {
+        name: tag.name,
+        description: tag.description || "",
+        operations: [],
+      };
+    });
+
+    for (const [path, item] of Object.entries(openapi.paths)) {
+      for (const method of ["get", "post", "put", "delete"]) {
+        const operation = item[method];
+
+        if (operation === undefined) {
+          continue;
+        }
+
+        const name = (operation.tags || [])[0];
+        let tag = tags.find((tag) => tag.name === name);
+
+        if (tag === undefined) {
+          tag = { name, description: "", operations: [] };
+          tags.push(tag);
+        }
+
+        tag.operations.push(operationItem(openapi, path, method, operation));
+      }
+    }
+
+    document.querySelector("#title").textContent = openapi.info.title;
+    document.querySelector("#description").textContent =
+      openapi.info.description || "";
+    document
+      .querySelector("#tags")
+      .replaceChildren(
+        ...tags
+          .filter((tag) => tag.operations.length > 0)
+          .map((tag) => tagSection(tag)),
+      );
+    document.querySelector("#error-reply").textContent = schemaText(
+      openapi,
+      { $ref: "#/components/schemas/ErrorReply" },
+      "",
+      [],
+    );
+    document.querySelector("#errors").hidden = false;
+  }
+
+  fetch("/api/openapi.json")
+    .then((data) => data.json())
+    .then(documentShow)
+    .catch((error) => showError(error));
+})();
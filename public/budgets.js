@@ -0,0 +1,104 @@
+"use strict";
+
+const application = Vue.createApp({
+  mounted() {
+    this.budgetsUpdate();
+  },
+
+  data() {
+    return {
+      loading: false,
+      error: false,
+      message: "",
+      month: "",
+      items: [],
+      budget: {
+        category: "",
+        amount: "",
+      },
+    };
+  },
+
+  methods: {
+    hasItems() {
+      return this.items.length > 0;
+    },
+
+    budgetValid() {
+      return this.budget.category !== "" && this.budget.amount > 0;
+    },
+
+    showError(error) {
+      this.loading = false;
+      this.error = true;
+      this.message = error;
+    },
+
+    request(method, url, body) {
+      this.loading = true;
+
+      const options = { method };
+
+      if (body !== undefined) {
+        options.headers = { "Content-Type": "application/json" };
+        options.body = JSON.stringify(body);
+      }
+
+      return fetch(url, options)
+        .then((data) => data.json())
+        .then((data) => {
+          this.loading = false;
+
+          if (!data.success) {
+            throw data.message;
+          }
+
+          this.error = false;
+          this.message = "";
+
+          return data;
+        });
+    },
+
+    budgetsUpdate() {
+      this.request("GET", "/api/v1/budgets")
+        .then((data) => {
+          this.month = data.month;
+          this.items = data.items.map((item) => {
+            return {
+              id: item.id,
+              category: item.category,
+              amount: item.amount.toFixed(2),
+              spent: item.spent.toFixed(2),
+              percent: Math.min(100, (100 * item.spent) / item.amount),
+              over: item.over,
+            };
+          });
+        })
+        .catch((error) => {
+          this.showError(error);
+          this.items = [];
+        });
+    },
+
+    budgetSave() {
+      this.request("PUT", "/api/v1/budgets", {
+        category: this.budget.category,
+        amount: parseFloat(this.budget.amount),
+      })
+        .then(() => {
+          this.budget.category = "";
+          this.budget.amount = "";
+          this.budgetsUpdate();
+        })
+        .catch((error) => this.showError(error));
+    },
+
+    budgetDelete(item) {
+      this.request("DELETE", `/api/v1/budgets/${item.id}`)
+        .then(() => this.budgetsUpdate())
+        .catch((error) => this.showError(error));
+    },
+  },
+});
+application.mount("#app");
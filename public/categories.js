@@ -11,6 +11,14 @@ const application = Vue.createApp({
       error: false,
       message: "",
       items: [],
+      bulk: {
+        category: "",
+        name: "",
+      },
+      rename: {
+        source: "",
+        target: "",
+      },
     };
   },
 
@@ -32,7 +40,7 @@ const application = Vue.createApp({
     },
 
     categoriesUpdate() {
-      fetch("/api/categories/list", { method: "POST" })
+      fetch("/api/v1/categories")
         .then((data) => data.json())
         .then((data) => {
           this.loading = false;
@@ -40,8 +48,11 @@ const application = Vue.createApp({
           this.items = data.items.map((item) => {
             return {
               product: item.product,
+              canonical: item.canonical,
               category: item.category,
               name: item.name,
+              suggestion: item.suggestion,
+              selected: false,
             };
           });
           this.message = "";
@@ -56,11 +67,95 @@ const application = Vue.createApp({
       this.loading = true;
     },
 
+    selectedProducts() {
+      return this.items
+        .filter((item) => item.selected)
+        .map((item) => item.product);
+    },
+
+    allSelected() {
+      return this.hasItems() && this.items.every((item) => item.selected);
+    },
+
+    selectAll(event) {
+      this.items.forEach((item) => {
+        item.selected = event.target.checked;
+      });
+    },
+
+    bulkValid() {
+      return (
+        this.selectedProducts().length > 0 &&
+        this.bulk.category !== "" &&
+        this.bulk.name !== ""
+      );
+    },
+
+    renameValid() {
+      return this.rename.source !== "" && this.rename.target !== "";
+    },
+
+    request(method, url, body) {
+      this.loading = true;
+
+      const options = { method };
+
+      if (body !== undefined) {
+        options.headers = { "Content-Type": "application/json" };
+        options.body = JSON.stringify(body);
+      }
+
+      return fetch(url, options)
+        .then((data) => data.json())
+        .then((data) => {
+          this.loading = false;
+
+          if (!data.success) {
+            throw data.message;
+          }
+
+          this.error = false;
+          this.message = "";
+
+          return data;
+        })
+        .catch((error) => {
+          this.loading = false;
+          this.error = true;
+          this.message = error;
+        });
+    },
+
+    bulkAssign() {
+      this.request("PUT", "/api/v1/products/categories", {
+        products: this.selectedProducts(),
+        category: this.bulk.category,
+        name: this.bulk.name,
+      }).then(() => this.categoriesUpdate());
+    },
+
+    bulkRename(url) {
+      this.request("POST", url, this.rename).then(() =>
+        this.categoriesUpdate()
+      );
+    },
+
+    suggestionConfidence(item) {
+      return (100 * item.suggestion.confidence).toFixed(0);
+    },
+
+    suggestionAccept(item) {
+      item.category = item.suggestion.category;
+      item.name = item.suggestion.name;
+    },
+
     productUpdate(item) {
-      fetch("/api/categories/update", {
-        method: "POST",
+      const product = encodeURIComponent(item.product);
+
+      fetch(`/api/v1/products/${product}/category`, {
+        method: "PUT",
         headers: { "Content-Type": "application/json" },
-        body: JSON.stringify(item),
+        body: JSON.stringify({ category: item.category, name: item.name }),
       })
         .then((data) => {
           this.loading = false;
@@ -0,0 +1,102 @@
+"use strict";
+
+const application = Vue.createApp({
+  mounted() {
+    this.membersUpdate();
+  },
+
+  data() {
+    return {
+      loading: false,
+      error: false,
+      message: "",
+      items: [],
+      member: {
+        login: "",
+        password: "",
+        role: "member",
+      },
+    };
+  },
+
+  methods: {
+    hasItems() {
+      return this.items.length > 0;
+    },
+
+    memberValid() {
+      return this.member.login !== "" && this.member.password !== "";
+    },
+
+    showError(error) {
+      this.loading = false;
+      this.error = true;
+      this.message = error;
+    },
+
+    request(method, url, body) {
+      this.loading = true;
+
+      const options = { method };
+
+      if (body !== undefined) {
+        options.headers = { "Content-Type": "application/json" };
+        options.body = JSON.stringify(body);
+      }
+
+      return fetch(url, options)
+        .then((data) => data.json())
+        .then((data) => {
+          this.loading = false;
+
+          if (!data.success) {
+            throw data.message;
+          }
+
+          this.error = false;
+          this.message = "";
+
+          return data;
+        });
+    },
+
+    membersUpdate() {
+      this.request("GET", "/api/v1/household/members")
+        .then((data) => {
+          this.items = data.items;
+        })
+        .catch((error) => {
+          this.showError(error);
+          this.items = [];
+        });
+    },
+
+    memberCreate() {
+      this.request("POST", "/api/v1/household/members", this.member)
+        .then(() => {
+          this.member.login = "";
+          this.member.password = "";
+          this.membersUpdate();
+        })
+        .catch((error) => this.showError(error));
+    },
+
+    memberRole(item) {
+      this.request("PUT", `/api/v1/household/members/${item.id}/role`, {
+        role: item.role,
+      })
+        .then(() => this.membersUpdate())
+        .catch((error) => {
+          this.showError(error);
+          this.membersUpdate();
+        });
+    },
+
+    memberDelete(item) {
+      this.request("DELETE", `/api/v1/household/members/${item.id}`)
+        .then(() => this.membersUpdate())
+        .catch((error) => this.showError(error));
+    },
+  },
+});
+application.mount("#app");
@@ -0,0 +1,56 @@
+"use strict";
+
+const application = Vue.createApp({
+  data() {
+    return {
+      loading: false,
+      error: false,
+      message: "",
+      registering: false,
+      login: "",
+      password: "",
+    };
+  },
+
+  methods: {
+    valid() {
+      return this.login !== "" && this.password !== "";
+    },
+
+    showError(error) {
+      this.loading = false;
+      this.error = true;
+      this.message = error;
+    },
+
+    submit() {
+      if (!this.valid()) {
+        return;
+      }
+
+      const url = this.registering
+        ? "/api/v1/users"
+        : "/api/v1/session";
+
+      this.loading = true;
+
+      fetch(url, {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({ login: this.login, password: this.password }),
+      })
+        .then((data) => data.json())
+        .then((data) => {
+          this.loading = false;
+
+          if (!data.success) {
+            throw data.message;
+          }
+
+          window.location.href = "/";
+        })
+        .catch((error) => this.showError(error));
+    },
+  },
+});
+application.mount("#app");
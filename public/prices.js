@@ -0,0 +1,163 @@
+"use strict";
+
+const COLORS = ["#2185d0", "#21ba45", "#db2828", "#f2711c", "#a333c8"];
+
+const application = Vue.createApp({
+  mounted() {
+    const params = new URLSearchParams(window.location.search);
+
+    this.productId = params.get("id") || "";
+    this.productsUpdate();
+    this.inflationUpdate();
+  },
+
+  data() {
+    return {
+      loading: false,
+      error: false,
+      message: "",
+      productId: "",
+      products: [],
+      prices: [],
+      months: [],
+    };
+  },
+
+  methods: {
+    hasPrices() {
+      return this.prices.length > 0;
+    },
+
+    hasMonths() {
+      return this.months.length > 0;
+    },
+
+    showError(error) {
+      this.loading = false;
+      this.error = true;
+      this.message = error;
+    },
+
+    request(url) {
+      this.loading = true;
+
+      return fetch(url)
+        .then((data) => data.json())
+        .then((data) => {
+          this.loading = false;
+
+          if (!data.success) {
+            throw data.message;
+          }
+
+          this.error = false;
+          this.message = "";
+
+          return data;
+        });
+    },
+
+    productsUpdate() {
+      this.request("/api/v1/products")
+        .then((data) => {
+          this.products = data.items;
+
+          if (this.productId !== "") {
+            this.pricesUpdate();
+          }
+        })
+        .catch((error) => this.showError(error));
+    },
+
+    pricesUpdate() {
+      window.history.replaceState(null, "", `?id=${this.productId}`);
+
+      this.request(`/api/v1/products/${this.productId}/prices`)
+        .then((data) => {
+          this.prices = data.prices
+            .filter((price) => price.unit_price !== null)
+            .map((price) => {
+              return {
+                date: price.date,
+                store: price.store,
+                product: price.product,
+                unitPrice: price.unit_price.toFixed(2),
+                unit: price.unit,
+              };
+            });
+          this.$nextTick(() => this.pricesChart());
+        })
+        .catch((error) => {
+          this.showError(error);
+          this.prices = [];
+        });
+    },
+
+    pricesChart() {
+      const stores = [...new Set(this.prices.map((price) => price.store))];
+      const datasets = stores.map((store, index) => {
+        return {
+          label: store || "Unknown store",
+          showLine: false,
+          backgroundColor: COLORS[index % COLORS.length],
+          data: this.prices
+            .filter((price) => price.store === store)
+            .map((price) => ({ x: price.date, y: price.unitPrice })),
+        };
+      });
+
+      if (this.priceChart) {
+        this.priceChart.destroy();
+      }
+
+      this.priceChart = new Chart(document.querySelector("#prices"), {
+        type: "line",
+        data: {
+          labels: [...new Set(this.prices.map((price) => price.date))],
+          datasets,
+        },
+      });
+    },
+
+    inflationUpdate() {
+      this.request("/api/v1/products/inflation")
+        .then((data) => {
+          this.months = data.months.map((month) => {
+            return {
+              month: month.month,
+              index: (100 * (month.index - 1)).toFixed(2),
+              cumulative: (100 * (month.cumulative - 1)).toFixed(2),
+              products: month.products,
+            };
+          });
+          this.$nextTick(() => this.inflationChart());
+        })
+        .catch((error) => {
+          this.showError(error);
+          this.months = [];
+        });
+    },
+
+    inflationChart() {
+      new Chart(document.querySelector("#inflation"), {
+        type: "line",
+        data: {
+          labels: this.months.map((month) => month.month),
+          datasets: [
+            {
+              label: "Cumulative inflation, %",
+              borderColor: COLORS[0],
+              data: this.months.map((month) => month.cumulative),
+            },
+            {
+              label: "Month over month, %",
+              borderColor: COLORS[1],
+              data: this.months.map((month) => month.index),
+            },
+          ],
+        },
+      });
+    },
+  },
+});
+application.mount("#app");
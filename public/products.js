@@ -0,0 +1,42 @@
+"use strict";
+
+const application = Vue.createApp({
+  mounted() {
+    this.productsUpdate();
+  },
+
+  data() {
+    return {
+      loading: false,
+      error: false,
+      message: "",
+      items: [],
+    };
+  },
+
+  methods: {
+    hasItems() {
+      return this.items.length > 0;
+    },
+
+    productsUpdate() {
+      fetch("/api/v1/products")
+        .then((data) => data.json())
+        .then((data) => {
+          this.loading = false;
+          this.error = false;
+          this.items = data.items;
+          this.message = "";
+        })
+        .catch((error) => {
+          this.loading = false;
+          this.error = true;
+          this.message = error;
+          this.items = [];
+        });
+
+      this.loading = true;
+    },
+  },
+});
+application.mount("#app");
@@ -0,0 +1,93 @@
+"use strict";
+
+const application = Vue.createApp({
+  mounted() {
+    this.receiptUpdate();
+  },
+
+  data() {
+    return {
+      loading: false,
+      error: false,
+      message: "",
+      receipt: null,
+    };
+  },
+
+  methods: {
+    formatTime(time) {
+      return time === null ? "" : new Date(time * 1000).toLocaleString();
+    },
+
+    formatSum(sum) {
+      return sum === null ? "" : sum.toFixed(2);
+    },
+
+    receiptUpdate() {
+      const key = new URLSearchParams(window.location.search).get("key");
+
+      if (key === null) {
+        this.error = true;
+        this.message = "Receipt key is missing";
+
+        return;
+      }
+
+      fetch(`/api/v1/receipts/${encodeURIComponent(key)}`)
+        .then((data) => data.json())
+        .then((data) => {
+          this.loading = false;
+
+          if (!data.success) {
+            throw data.message;
+          }
+
+          const receipt = data.receipt;
+
+          this.error = false;
+          this.message = "";
+          this.receipt = {
+            seller: receipt.seller || "",
+            address: receipt.address || "",
+            inn: receipt.inn || "",
+            time:
+              receipt.time === null
+                ? receipt.items[0].date
+                : new Date(receipt.time).toLocaleString(),
+            total: this.formatSum(receipt.total),
+            cash: this.formatSum(receipt.cash),
+            card: this.formatSum(receipt.card),
+            fiscalDrive: receipt.fiscal_drive || "",
+            fiscalDocument: receipt.fiscal_document || "",
+            fiscalSign: receipt.fiscal_sign || "",
+            register: receipt.register || "",
+            scanned: this.formatTime(receipt.scanned),
+            member: receipt.member || "",
+            items: receipt.items.map((item) => {
+              return {
+                categorized: item.type === "Categorized",
+                product: item.product,
+                category: item.category,
+                name: item.name,
+                quantity: item.quantity.toFixed(3),
+                sum: item.sum.toFixed(2),
+                unitPrice:
+                  item.unit_price === null
+                    ? ""
+                    : `${item.unit_price.toFixed(2)} / ${item.unit}`,
+              };
+            }),
+          };
+        })
+        .catch((error) => {
+          this.loading = false;
+          this.error = true;
+          this.message = error;
+          this.receipt = null;
+        });
+
+      this.loading = true;
+    },
+  },
+});
+application.mount("#app");
@@ -19,8 +19,12 @@ const application = Vue.createApp({
       return this.items.length > 0;
     },
 
+    receiptLink(item) {
+      return `/receipt.html?key=${encodeURIComponent(item.ticket)}`;
+    },
+
     receiptUpdate() {
-      fetch("/api/tickets/list", { method: "POST" })
+      fetch("/api/v1/receipts")
         .then((data) => data.json())
         .then((data) => {
           this.loading = false;
@@ -34,6 +38,12 @@ const application = Vue.createApp({
               name: item.name,
               quantity: item.quantity.toFixed(3),
               sum: item.sum.toFixed(2),
+              unitPrice:
+                item.unit_price === null
+                  ? ""
+                  : `${item.unit_price.toFixed(2)} / ${item.unit}`,
+              ticket: item.ticket,
+              member: item.member || "",
             };
           });
           this.message = "";
@@ -49,19 +59,46 @@ const application = Vue.createApp({
     },
 
     receiptClear() {
-      fetch("/api/tickets/clear", { method: "POST" })
+      fetch("/api/v1/receipts", { method: "DELETE" })
         .then((data) => data.json())
         .then((data) => {
           this.loading = false;
+
+          if (!data.success) {
+            throw data.message;
+          }
+
           this.error = false;
           this.items = [];
           this.message = "";
         })
         .catch((error) => {
           this.loading = false;
-          this.error = false;
+          this.error = true;
+          this.message = error;
+        });
+
+      this.loading = true;
+    },
+
+    receiptDelete(item) {
+      const ticket = encodeURIComponent(item.ticket);
+
+      fetch(`/api/v1/receipts/${ticket}`, { method: "DELETE" })
+        .then((data) => data.json())
+        .then((data) => {
+          this.loading = false;
+
+          if (!data.success) {
+            throw data.message;
+          }
+
+          this.receiptUpdate();
+        })
+        .catch((error) => {
+          this.loading = false;
+          this.error = true;
           this.message = error;
-          this.items = [];
         });
 
       this.loading = true;
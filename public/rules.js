@@ -0,0 +1,152 @@
+"use strict";
+
+const application = Vue.createApp({
+  mounted() {
+    this.rulesUpdate();
+  },
+
+  data() {
+    return {
+      loading: false,
+      error: false,
+      message: "",
+      overwrite: false,
+      items: [],
+      changes: [],
+      rule: {
+        id: null,
+        priority: 0,
+        kind: "substring",
+        pattern: "",
+        inn: "",
+        category: "",
+        name: "",
+      },
+    };
+  },
+
+  methods: {
+    hasItems() {
+      return this.items.length > 0;
+    },
+
+    hasChanges() {
+      return this.changes.length > 0;
+    },
+
+    ruleValid(rule) {
+      return rule.pattern !== "" && rule.category !== "" && rule.name !== "";
+    },
+
+    rulePattern(id) {
+      const rule = this.items.find((item) => item.id === id);
+
+      return rule ? rule.pattern : id;
+    },
+
+    showError(error) {
+      this.loading = false;
+      this.error = true;
+      this.message = error;
+    },
+
+    request(method, url, body) {
+      this.loading = true;
+
+      const options = { method };
+
+      if (body !== undefined) {
+        options.headers = { "Content-Type": "application/json" };
+        options.body = JSON.stringify(body);
+      }
+
+      return fetch(url, options)
+        .then((data) => data.json())
+        .then((data) => {
+          this.loading = false;
+
+          if (!data.success) {
+            throw data.message;
+          }
+
+          this.error = false;
+          this.message = "";
+
+          return data;
+        });
+    },
+
+    rulesUpdate() {
+      this.request("GET", "/api/v1/rules")
+        .then((data) => {
+          this.items = data.items.map((item) => {
+            return {
+              id: item.id,
+              priority: item.priority,
+              kind: item.kind,
+              pattern: item.pattern,
+              inn: item.inn || "",
+              category: item.category,
+              name: item.name,
+            };
+          });
+        })
+        .catch((error) => {
+          this.showError(error);
+          this.items = [];
+        });
+    },
+
+    ruleSave(rule) {
+      const body = Object.assign({}, rule, {
+        priority: parseInt(rule.priority, 10) || 0,
+      });
+
+      const request = rule.id
+        ? this.request("PUT", `/api/v1/rules/${rule.id}`, body)
+        : this.request("POST", "/api/v1/rules", body);
+
+      request
+        .then(() => {
+          if (rule === this.rule) {
+            this.rule.pattern = "";
+            this.rule.inn = "";
+          }
+
+          this.rulesUpdate();
+        })
+        .catch((error) => this.showError(error));
+    },
+
+    ruleDelete(rule) {
+      this.request("DELETE", `/api/v1/rules/${rule.id}`)
+        .then(() => this.rulesUpdate())
+        .catch((error) => this.showError(error));
+    },
+
+    rulesPreview() {
+      this.request("POST", "/api/v1/rules/preview", {
+        overwrite: this.overwrite,
+      })
+        .then((data) => {
+          this.changes = data.changes;
+        })
+        .catch((error) => {
+          this.showError(error);
+          this.changes = [];
+        });
+    },
+
+    rulesApply() {
+      this.request("POST", "/api/v1/rules/apply", {
+        overwrite: this.overwrite,
+      })
+        .then((data) => {
+          this.changes = [];
+          this.message = `Updated ${data.count} products`;
+        })
+        .catch((error) => this.showError(error));
+    },
+  },
+});
+application.mount("#app");
@@ -16,6 +16,7 @@ const application = Vue.createApp({
       success: false,
       error: false,
       message: "",
+      warnings: [],
     };
   },
 
@@ -37,7 +38,7 @@ const application = Vue.createApp({
 
     updateQrCode(qrCode) {
       if (this.qrCode !== qrCode) {
-        fetch("/api/qrcode", {
+        fetch("/api/v1/receipts", {
           method: "POST",
           headers: { "Content-Type": "application/json" },
           body: JSON.stringify(qrCode),
@@ -47,7 +48,8 @@ const application = Vue.createApp({
             this.loading = false;
             this.success = data.success;
             this.error = !data.success;
-            this.message = data.message;
+            this.message = data.message || "";
+            this.warnings = data.warnings || [];
             this.backgroundFlash(data.success ? "green" : "red");
           })
           .catch((error) => {
@@ -55,6 +57,7 @@ const application = Vue.createApp({
             this.success = false;
             this.error = true;
             this.message = error;
+            this.warnings = [];
             this.backgroundFlash("red");
           });
 
@@ -0,0 +1,24 @@
+"use strict";
+
+(() => {
+  const originalFetch = window.fetch;
+
+  window.fetch = (...args) =>
+    originalFetch(...args).then((response) => {
+      if (response.status === 401) {
+        window.location.href = "/login.html";
+      }
+
+      return response;
+    });
+
+  const logout = document.querySelector("#logout");
+
+  if (logout) {
+    logout.addEventListener("click", () => {
+      originalFetch("/api/v1/session", { method: "DELETE" }).then(() => {
+        window.location.href = "/login.html";
+      });
+    });
+  }
+})();
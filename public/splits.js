@@ -0,0 +1,148 @@
+"use strict";
+
+const application = Vue.createApp({
+  mounted() {
+    this.splitsUpdate();
+  },
+
+  data() {
+    return {
+      loading: false,
+      error: false,
+      message: "",
+      person: "",
+      people: [],
+      receipts: [],
+      balances: [],
+      transfers: [],
+    };
+  },
+
+  methods: {
+    personName(id) {
+      const person = this.people.find((item) => item.id === id);
+
+      return person === undefined ? "" : person.name;
+    },
+
+    showError(error) {
+      this.loading = false;
+      this.error = true;
+      this.message = error;
+    },
+
+    request(method, url, body) {
+      this.loading = true;
+
+      const options = { method };
+
+      if (body !== undefined) {
+        options.headers = { "Content-Type": "application/json" };
+        options.body = JSON.stringify(body);
+      }
+
+      return fetch(url, options)
+        .then((data) => data.json())
+        .then((data) => {
+          this.loading = false;
+
+          if (!data.success) {
+            throw data.message;
+          }
+
+          this.error = false;
+          this.message = "";
+
+          return data;
+        });
+    },
+
+    splitsUpdate() {
+      this.request("GET", "/api/v1/splits/people")
+        .then((data) => {
+          this.people = data.items;
+
+          return this.request("GET", "/api/v1/splits/receipts");
+        })
+        .then((data) => {
+          this.receipts = data.receipts.map((receipt) => {
+            return {
+              ticket: receipt.ticket,
+              date: receipt.date,
+              seller: receipt.seller || "",
+              payer: receipt.payer,
+              items: receipt.items.map((item) => {
+                const shares = {};
+
+                item.shares.forEach((share) => {
+                  shares[share.person] = share.share;
+                });
+
+                return {
+                  id: item.id,
+                  product: item.product,
+                  sum: item.sum,
+                  shares: shares,
+                };
+              }),
+            };
+          });
+
+          this.balanceUpdate();
+        })
+        .catch((error) => this.showError(error));
+    },
+
+    balanceUpdate() {
+      this.request("GET", "/api/v1/splits/balance")
+        .then((data) => {
+          this.balances = data.balances;
+          this.transfers = data.transfers;
+        })
+        .catch((error) => this.showError(error));
+    },
+
+    personCreate() {
+      this.request("POST", "/api/v1/splits/people", { name: this.person })
+        .then(() => {
+          this.person = "";
+          this.splitsUpdate();
+        })
+        .catch((error) => this.showError(error));
+    },
+
+    personDelete(item) {
+      this.request("DELETE", `/api/v1/splits/people/${item.id}`)
+        .then(() => this.splitsUpdate())
+        .catch((error) => this.showError(error));
+    },
+
+    payerSave(receipt) {
+      const ticket = encodeURIComponent(receipt.ticket);
+
+      this.request("PUT", `/api/v1/splits/receipts/${ticket}/payer`, {
+        person: receipt.payer,
+      })
+        .then(() => this.balanceUpdate())
+        .catch((error) => this.showError(error));
+    },
+
+    sharesSave(item) {
+      const shares = Object.keys(item.shares)
+        .map((person) => {
+          return {
+            person: parseInt(person),
+            share: parseFloat(item.shares[person]),
+          };
+        })
+        .filter((share) => share.share > 0);
+
+      this.request("PUT", `/api/v1/splits/items/${item.id}/shares`, {
+        shares: shares,
+      })
+        .then(() => this.balanceUpdate())
+        .catch((error) => this.showError(error));
+    },
+  },
+});
+application.mount("#app");
@@ -0,0 +1,60 @@
+"use strict";
+
+const application = Vue.createApp({
+  mounted() {
+    this.storesUpdate();
+  },
+
+  data() {
+    return {
+      loading: false,
+      error: false,
+      message: "",
+      items: [],
+    };
+  },
+
+  methods: {
+    hasItems() {
+      return this.items.length > 0;
+    },
+
+    storesUpdate() {
+      fetch("/api/v1/stores/compare")
+        .then((data) => data.json())
+        .then((data) => {
+          this.loading = false;
+          this.error = false;
+          this.items = data.items.map((item) => {
+            return {
+              id: item.id,
+              title: item.title,
+              unit: item.unit,
+              stores: item.stores.map((store) => {
+                return {
+                  name: store.seller
+                    ? `${store.seller}, ${store.store}`
+                    : store.store,
+                  latestDate: store.latest_date,
+                  latestPrice: store.latest_price.toFixed(2),
+                  averagePrice: store.average_price.toFixed(2),
+                  purchases: store.purchases,
+                  cheapest: store.cheapest,
+                };
+              }),
+            };
+          });
+          this.message = "";
+        })
+        .catch((error) => {
+          this.loading = false;
+          this.error = true;
+          this.message = error;
+          this.items = [];
+        });
+
+      this.loading = true;
+    },
+  },
+});
+application.mount("#app");
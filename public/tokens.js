@@ -0,0 +1,99 @@
+"use strict";
+
+const application = Vue.createApp({
+  mounted() {
+    this.tokensUpdate();
+  },
+
+  data() {
+    return {
+      loading: false,
+      error: false,
+      message: "",
+      token: "",
+      items: [],
+      item: {
+        name: "",
+        scope: "scan",
+      },
+    };
+  },
+
+  methods: {
+    hasItems() {
+      return this.items.length > 0;
+    },
+
+    formatTime(time) {
+      return time === null ? "" : new Date(time * 1000).toLocaleString();
+    },
+
+    showError(error) {
+      this.loading = false;
+      this.error = true;
+      this.message = error;
+    },
+
+    request(method, url, body) {
+      this.loading = true;
+
+      const options = { method };
+
+      if (body !== undefined) {
+        options.headers = { "Content-Type": "application/json" };
+        options.body = JSON.stringify(body);
+      }
+
+      return fetch(url, options)
+        .then((data) => data.json())
+        .then((data) => {
+          this.loading = false;
+
+          if (!data.success) {
+            throw data.message;
+          }
+
+          this.error = false;
+          this.message = "";
+
+          return data;
+        });
+    },
+
+    tokensUpdate() {
+      this.request("GET", "/api/v1/tokens")
+        .then((data) => {
+          this.items = data.items.map((item) => {
+            return {
+              id: item.id,
+              name: item.name,
+              scope: item.scope,
+              created: this.formatTime(item.created),
+              used: this.formatTime(item.used),
+            };
+          });
+        })
+        .catch((error) => {
+          this.showError(error);
+          this.items = [];
+        });
+    },
+
+    tokenCreate() {
+      this.request("POST", "/api/v1/tokens", this.item)
+        .then((data) => {
+          this.token = data.token;
+          this.item.name = "";
+          this.tokensUpdate();
+        })
+        .catch((error) => this.showError(error));
+    },
+
+    tokenDelete(token) {
+      this.request("DELETE", `/api/v1/tokens/${token.id}`)
+        .then(() => this.tokensUpdate())
+        .catch((error) => this.showError(error));
+    },
+  },
+});
+application.mount("#app");
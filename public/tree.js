@@ -0,0 +1,117 @@
+"use strict";
+
+const application = Vue.createApp({
+  mounted() {
+    this.treeUpdate();
+  },
+
+  data() {
+    return {
+      loading: false,
+      error: false,
+      message: "",
+      nodes: [],
+      rootName: "",
+    };
+  },
+
+  methods: {
+    hasNodes() {
+      return this.nodes.length > 0;
+    },
+
+    indent(node) {
+      return { "padding-left": `${node.depth * 2}em` };
+    },
+
+    showError(error) {
+      this.loading = false;
+      this.error = true;
+      this.message = error;
+    },
+
+    request(method, url, body) {
+      this.loading = true;
+
+      const options = { method };
+
+      if (body !== undefined) {
+        options.headers = { "Content-Type": "application/json" };
+        options.body = JSON.stringify(body);
+      }
+
+      return fetch(url, options)
+        .then((data) => data.json())
+        .then((data) => {
+          this.loading = false;
+
+          if (!data.success) {
+            throw data.message;
+          }
+
+          this.error = false;
+          this.message = "";
+
+          return data;
+        });
+    },
+
+    treeUpdate() {
+      this.request("GET", "/api/v1/categories/tree")
+        .then((data) => {
+          this.nodes = data.nodes.map((node) => {
+            return {
+              id: node.id,
+              parent: node.parent,
+              name: node.name,
+              path: node.path,
+              depth: node.depth,
+              total: node.total.toFixed(2),
+              newName: node.name,
+              newParent: node.parent,
+              mergeTarget: null,
+              childName: "",
+            };
+          });
+        })
+        .catch((error) => {
+          this.showError(error);
+          this.nodes = [];
+        });
+    },
+
+    nodeCreate(parent, name) {
+      this.request("POST", "/api/v1/categories", { parent, name })
+        .then(() => {
+          this.rootName = "";
+          this.treeUpdate();
+        })
+        .catch((error) => this.showError(error));
+    },
+
+    nodeRename(node) {
+      this.request("PUT", `/api/v1/categories/${node.id}/name`, {
+        name: node.newName,
+      })
+        .then(() => this.treeUpdate())
+        .catch((error) => this.showError(error));
+    },
+
+    nodeMove(node) {
+      this.request("PUT", `/api/v1/categories/${node.id}/parent`, {
+        parent: node.newParent,
+      })
+        .then(() => this.treeUpdate())
+        .catch((error) => this.showError(error));
+    },
+
+    nodeMerge(node) {
+      this.request("POST", `/api/v1/categories/${node.id}/merge`, {
+        target: node.mergeTarget,
+      })
+        .then(() => this.treeUpdate())
+        .catch((error) => this.showError(error));
+    },
+  },
+});
+application.mount("#app");
@@ -0,0 +1,200 @@
+use serde::Serialize;
+use std::error::Error;
+use std::fmt::Display;
+use utoipa::ToSchema;
+use warp::body::BodyDeserializeError;
+use warp::http::StatusCode;
+use warp::reject::Reject;
+use warp::reply::Json;
+use warp::reply::WithStatus;
+use warp::Rejection;
+
+const INTERNAL_MESSAGE: &str = "Internal server error";
+
+/// Errors of API handlers, every variant is replied with its own HTTP status
+/// and a stable `code`, so clients do not need to parse messages.
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(String),
+    Unauthorized(String),
+    Forbidden(String),
+    NotFound(String),
+    Conflict(String),
+    Ofd(String),
+    Internal(String),
+}
+
+impl ApiError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden(_) => StatusCode::FORBIDDEN,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::Ofd(_) => StatusCode::BAD_GATEWAY,
+            ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::BadRequest(_) => "bad_request",
+            ApiError::Unauthorized(_) => "unauthorized",
+            ApiError::Forbidden(_) => "forbidden",
+            ApiError::NotFound(_) => "not_found",
+            ApiError::Conflict(_) => "conflict",
+            ApiError::Ofd(_) => "ofd_error",
+            ApiError::Internal(_) => "internal_error",
+        }
+    }
+
+    /// Message replied to the client. Internal errors are replied with a
+    /// generic message, their details are logged by [`OrReject::or_reject`].
+    pub fn message(&self) -> &str {
+        match self {
+            ApiError::BadRequest(message)
+            | ApiError::Unauthorized(message)
+            | ApiError::Forbidden(message)
+            | ApiError::NotFound(message)
+            | ApiError::Conflict(message)
+            | ApiError::Ofd(message) => message,
+            ApiError::Internal(_) => INTERNAL_MESSAGE,
+        }
+    }
+}
+
+impl Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiError::Internal(message) => f.write_str(message),
+            _ => f.write_str(self.message()),
+        }
+    }
+}
+
+impl Reject for ApiError {}
+
+impl From<receipt_check::Error> for ApiError {
+    fn from(value: receipt_check::Error) -> Self {
+        let message = value.to_string();
+
+        match value {
+            receipt_check::Error::Invalid(_) => ApiError::BadRequest(message),
+            receipt_check::Error::NotFound(_) => ApiError::NotFound(message),
+            receipt_check::Error::Duplicate(_) => ApiError::Conflict(message),
+            receipt_check::Error::Ofd(_) => ApiError::Ofd(message),
+            receipt_check::Error::Database(_) => ApiError::Internal(message),
+        }
+    }
+}
+
+impl From<Box<dyn Error>> for ApiError {
+    fn from(value: Box<dyn Error>) -> Self {
+        match value.downcast::<receipt_check::Error>() {
+            Ok(error) => ApiError::from(*error),
+            Err(error) => ApiError::Internal(error.to_string()),
+        }
+    }
+}
+
+impl From<argon2::password_hash::Error> for ApiError {
+    fn from(value: argon2::password_hash::Error) -> Self {
+        ApiError::Internal(value.to_string())
+    }
+}
+
+/// Turns handler errors into rejections carrying [`ApiError`].
+pub trait OrReject<T> {
+    /// Logs the error with `message` and rejects the request.
+    fn or_reject(self, message: &str) -> Result<T, Rejection>;
+}
+
+impl<T, E> OrReject<T> for Result<T, E>
+where
+    E: Into<ApiError> + Display,
+{
+    fn or_reject(self, message: &str) -> Result<T, Rejection> {
+        self.map_err(|error| {
+            warn!("{}: {}", message, error);
+
+            warp::reject::custom(error.into())
+        })
+    }
+}
+
+/// Reply of failed API requests.
+#[derive(Serialize, ToSchema)]
+pub struct ErrorReply<'a> {
+    success: bool,
+    /// One of `bad_request`, `unauthorized`, `forbidden`, `not_found`,
+    /// `conflict`, `ofd_error` and `internal_error`.
+    code: &'a str,
+    message: &'a str,
+}
+
+/// Replies to [`ApiError`] rejections and malformed JSON bodies with the
+/// status and code of the error. Other rejections are passed through.
+pub async fn recover(rejection: Rejection) -> Result<impl warp::Reply, Rejection> {
+    if let Some(error) = rejection.find::<ApiError>() {
+        return Ok(reply(error.status(), error.code(), error.message()));
+    }
+
+    if let Some(error) = rejection.find::<BodyDeserializeError>() {
+        let message = error.to_string();
+
+        return Ok(reply(StatusCode::BAD_REQUEST, "bad_request", &message));
+    }
+
+    Err(rejection)
+}
+
+fn reply(status: StatusCode, code: &str, message: &str) -> WithStatus<Json> {
+    let reply = warp::reply::json(&ErrorReply {
+        success: false,
+        code,
+        message,
+    });
+
+    warp::reply::with_status(reply, status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use warp::Reply;
+
+    async fn body(rejection: Rejection) -> (StatusCode, String) {
+        let response = match recover(rejection).await {
+            Ok(reply) => reply.into_response(),
+            Err(_) => panic!("Rejection is not recovered"),
+        };
+        let status = response.status();
+        let body = warp::hyper::body::to_bytes(response.into_body())
+            .await
+            .unwrap();
+
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn internal_error_hides_details() {
+        let error = ApiError::Internal("no such table: households".into());
+        let (status, body) = body(warp::reject::custom(error)).await;
+
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(body.contains("\"code\":\"internal_error\""));
+        assert!(body.contains(INTERNAL_MESSAGE));
+        assert!(!body.contains("households"));
+    }
+
+    #[tokio::test]
+    async fn not_found_keeps_message() {
+        let error = ApiError::from(receipt_check::Error::NotFound("Person 7 not found".into()));
+        let (status, body) = body(warp::reject::custom(error)).await;
+
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(body.contains("\"code\":\"not_found\""));
+        assert!(body.contains("Person 7 not found"));
+    }
+}
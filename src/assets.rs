@@ -0,0 +1,92 @@
+use crate::hex;
+use rust_embed::RustEmbed;
+use std::convert::Infallible;
+use std::path::Path;
+use warp::filters::BoxedFilter;
+use warp::http::header::CACHE_CONTROL;
+use warp::http::header::CONTENT_TYPE;
+use warp::http::header::ETAG;
+use warp::http::Response;
+use warp::http::StatusCode;
+use warp::hyper::Body;
+use warp::path::Tail;
+use warp::reply::Reply;
+use warp::Filter;
+use warp::Rejection;
+
+const INDEX: &str = "scanner.html";
+
+/// Web interface files compiled into the executable.
+#[derive(RustEmbed)]
+#[folder = "public/"]
+struct Assets;
+
+/// Serves the scanner page on `/`, from the directory when it is given.
+pub fn index(public_dir: Option<&Path>) -> BoxedFilter<(Box<dyn Reply>,)> {
+    let index = warp::get().and(warp::path::end());
+
+    match public_dir {
+        Some(public_dir) => index
+            .and(warp::fs::file(public_dir.join(INDEX)))
+            .map(|file| Box::new(file) as Box<dyn Reply>)
+            .boxed(),
+        None => index
+            .and(if_none_match())
+            .and_then(|etag| embedded(INDEX.to_string(), etag))
+            .boxed(),
+    }
+}
+
+/// Serves web interface files, from the directory when it is given.
+pub fn public(public_dir: Option<&Path>) -> BoxedFilter<(Box<dyn Reply>,)> {
+    match public_dir {
+        Some(public_dir) => warp::get()
+            .and(warp::fs::dir(public_dir.to_path_buf()))
+            .map(|file| Box::new(file) as Box<dyn Reply>)
+            .boxed(),
+        None => warp::get()
+            .and(warp::path::tail())
+            .and(if_none_match())
+            .and_then(|tail: Tail, etag| embedded(tail.as_str().to_string(), etag))
+            .boxed(),
+    }
+}
+
+fn if_none_match() -> impl Filter<Extract = (Option<String>,), Error = Infallible> + Clone {
+    warp::header::optional::<String>("if-none-match").or_else(|_| async { Ok((None,)) })
+}
+
+async fn embedded(
+    path: String,
+    if_none_match: Option<String>,
+) -> Result<Box<dyn Reply>, Rejection> {
+    let file = Assets::get(&path).ok_or_else(warp::reject::not_found)?;
+    let etag = format!("\"{}\"", hex::encode(&file.metadata.sha256_hash()));
+    let matches = if_none_match
+        .map(|value| {
+            value
+                .split(',')
+                .any(|tag| tag.trim() == etag || tag.trim() == "*")
+        })
+        .unwrap_or(false);
+    let builder = Response::builder()
+        .header(ETAG, &etag)
+        .header(CACHE_CONTROL, "no-cache");
+
+    let response = if matches {
+        builder.status(StatusCode::NOT_MODIFIED).body(Body::empty())
+    } else {
+        builder
+            .header(CONTENT_TYPE, file.metadata.mimetype())
+            .body(Body::from(file.data.into_owned()))
+    };
+
+    match response {
+        Ok(response) => Ok(Box::new(response)),
+        Err(error) => {
+            warn!("Failed to build response for {}: {}", path, error);
+
+            Err(warp::reject::not_found())
+        }
+    }
+}
@@ -0,0 +1,295 @@
+use crate::database::Database;
+use crate::options::Options;
+use crate::users;
+use argon2::password_hash::PasswordHash;
+use argon2::password_hash::PasswordVerifier;
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::sync::Arc;
+use std::sync::RwLock;
+use std::time::Duration;
+use std::time::Instant;
+use warp::http::header::WWW_AUTHENTICATE;
+use warp::http::StatusCode;
+use warp::reject::Reject;
+use warp::Filter;
+use warp::Rejection;
+
+/// Verified credentials are trusted without hashing again for this long.
+const VERIFIED_LIFETIME: Duration = Duration::from_secs(10 * 60);
+/// Most verified credentials kept, older ones are dropped when it is reached.
+const VERIFIED_CAPACITY: usize = 256;
+
+/// Rejects requests without valid HTTP Basic credentials.
+#[derive(Debug)]
+pub struct BasicUnauthorized;
+
+impl Reject for BasicUnauthorized {}
+
+/// Users allowed by HTTP Basic authentication with their Argon2 password
+/// hashes. Authentication is disabled when no users are configured.
+/// Credentials hashes are cached with their verification time, so browsers
+/// sending them on every request are not slowed down by Argon2.
+#[derive(Debug, Clone, Default)]
+pub struct BasicAuth {
+    users: Arc<Vec<(String, String)>>,
+    verified: Arc<RwLock<HashMap<String, Instant>>>,
+}
+
+impl BasicAuth {
+    pub fn new(options: &Options) -> Result<Self, Box<dyn Error>> {
+        let mut users = Vec::new();
+
+        if let (Some(user), Some(hash)) = (options.basic_user(), options.basic_password_hash()) {
+            users.push((user.to_string(), hash.to_string()));
+        }
+
+        if let Some(path) = options.basic_auth_file() {
+            let content = fs::read_to_string(path)
+                .map_err(|error| format!("Failed to read {}: {}", path.display(), error))?;
+
+            for line in content.lines() {
+                let line = line.trim();
+
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+
+                let (user, hash) = line
+                    .split_once(':')
+                    .ok_or_else(|| format!("Invalid basic auth line: {}", line))?;
+                users.push((user.to_string(), hash.to_string()));
+            }
+        }
+
+        for (user, hash) in &users {
+            PasswordHash::new(hash)
+                .map_err(|error| format!("Invalid password hash of {}: {}", user, error))?;
+        }
+
+        Ok(Self {
+            users: Arc::new(users),
+            verified: Arc::default(),
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.users.is_empty()
+    }
+
+    /// Checks the `Authorization` header value. Bearer API tokens are let
+    /// through when valid, so scripts do not need both credentials.
+    async fn check(
+        &self,
+        authorization: Option<String>,
+        database: Database,
+    ) -> Result<(), Rejection> {
+        if !self.is_enabled() {
+            return Ok(());
+        }
+
+        let authorization = authorization.ok_or_else(|| warp::reject::custom(BasicUnauthorized))?;
+
+        if let Some(token) = authorization.strip_prefix("Bearer ") {
+            return match database
+                .select_token_user(&users::hash_token(token.trim()), users::now())
+                .await
+            {
+                Ok(Some(_)) => Ok(()),
+                Ok(None) => Err(warp::reject::custom(BasicUnauthorized)),
+                Err(error) => {
+                    warn!("Failed to read token: {}", error);
+
+                    Err(warp::reject::custom(BasicUnauthorized))
+                }
+            };
+        }
+
+        let credentials = authorization
+            .strip_prefix("Basic ")
+            .ok_or_else(|| warp::reject::custom(BasicUnauthorized))?;
+        let key = users::hash_token(credentials);
+
+        if let Some(time) = self.verified.read().unwrap().get(&key) {
+            if time.elapsed() < VERIFIED_LIFETIME {
+                return Ok(());
+            }
+        }
+
+        match self.verify(credentials) {
+            true => {
+                self.remember(key);
+
+                Ok(())
+            }
+            false => {
+                warn!("Invalid basic auth credentials");
+
+                Err(warp::reject::custom(BasicUnauthorized))
+            }
+        }
+    }
+
+    /// Caches verified credentials, dropping expired ones and the oldest
+    /// ones past [`VERIFIED_CAPACITY`].
+    fn remember(&self, key: String) {
+        let mut verified = self.verified.write().unwrap();
+        verified.retain(|_, time| time.elapsed() < VERIFIED_LIFETIME);
+
+        if verified.len() >= VERIFIED_CAPACITY {
+            if let Some(oldest) = verified
+                .iter()
+                .min_by_key(|(_, time)| **time)
+                .map(|(key, _)| key.clone())
+            {
+                verified.remove(&oldest);
+            }
+        }
+
+        verified.insert(key, Instant::now());
+    }
+
+    fn verify(&self, credentials: &str) -> bool {
+        let decoded = match STANDARD.decode(credentials.trim()) {
+            Ok(decoded) => decoded,
+            Err(_) => return false,
+        };
+        let decoded = String::from_utf8_lossy(&decoded);
+        let (user, password) = match decoded.split_once(':') {
+            Some(pair) => pair,
+            None => return false,
+        };
+
+        self.users
+            .iter()
+            .filter(|(name, _)| name == user)
+            .filter_map(|(_, hash)| PasswordHash::new(hash).ok())
+            .any(|hash| {
+                Argon2::default()
+                    .verify_password(password.as_bytes(), &hash)
+                    .is_ok()
+            })
+    }
+}
+
+/// Requires HTTP Basic authentication on every route when it is enabled.
+pub fn basic_auth(
+    auth: BasicAuth,
+    database: Database,
+) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::header::optional::<String>("authorization")
+        .and(warp::any().map(move || (auth.clone(), database.clone())))
+        .and_then(
+            |authorization, (auth, database): (BasicAuth, Database)| async move {
+                auth.check(authorization, database).await
+            },
+        )
+        .untuple_one()
+}
+
+/// Asks the browser for credentials on [`BasicUnauthorized`] rejections.
+pub async fn recover(rejection: Rejection) -> Result<impl warp::Reply, Rejection> {
+    if rejection.find::<BasicUnauthorized>().is_some() {
+        let reply = warp::reply::with_status("Unauthorized", StatusCode::UNAUTHORIZED);
+
+        return Ok(warp::reply::with_header(
+            reply,
+            WWW_AUTHENTICATE,
+            "Basic realm=\"receipt-check\"",
+        ));
+    }
+
+    Err(rejection)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::SCOPE_READ;
+
+    fn auth() -> BasicAuth {
+        let hash = users::hash_password("password1").unwrap();
+
+        BasicAuth {
+            users: Arc::new(vec![("alice".to_string(), hash)]),
+            verified: Arc::default(),
+        }
+    }
+
+    fn basic(credentials: &str) -> String {
+        format!("Basic {}", STANDARD.encode(credentials))
+    }
+
+    async fn allowed(auth: &BasicAuth, database: &Database, authorization: Option<&str>) -> bool {
+        let mut request = warp::test::request();
+
+        if let Some(authorization) = authorization {
+            request = request.header("authorization", authorization);
+        }
+
+        request
+            .filter(&basic_auth(auth.clone(), database.clone()))
+            .await
+            .is_ok()
+    }
+
+    #[tokio::test]
+    async fn disabled_auth_allows_everything() {
+        let database = Database::new(":memory:").unwrap();
+
+        assert!(allowed(&BasicAuth::default(), &database, None).await);
+    }
+
+    #[tokio::test]
+    async fn basic_credentials_are_verified_and_cached() {
+        let database = Database::new(":memory:").unwrap();
+        let auth = auth();
+
+        assert!(!allowed(&auth, &database, None).await);
+        assert!(!allowed(&auth, &database, Some(&basic("alice:password2"))).await);
+        assert!(!allowed(&auth, &database, Some(&basic("bob:password1"))).await);
+        assert!(auth.verified.read().unwrap().is_empty());
+        assert!(allowed(&auth, &database, Some(&basic("alice:password1"))).await);
+        assert_eq!(auth.verified.read().unwrap().len(), 1);
+        assert!(allowed(&auth, &database, Some(&basic("alice:password1"))).await);
+    }
+
+    #[tokio::test]
+    async fn bearer_tokens_are_checked() {
+        let database = Database::new(":memory:").unwrap();
+        let auth = auth();
+        let user = database.insert_user("alice", "-").await.unwrap();
+        let token = users::new_token();
+        database
+            .insert_token(
+                user.id(),
+                "script",
+                &users::hash_token(&token),
+                SCOPE_READ,
+                0,
+            )
+            .await
+            .unwrap();
+
+        let valid = format!("Bearer {}", token);
+        let unknown = format!("Bearer {}", users::new_token());
+
+        assert!(allowed(&auth, &database, Some(&valid)).await);
+        assert!(!allowed(&auth, &database, Some(&unknown)).await);
+    }
+
+    #[test]
+    fn verified_cache_is_bounded() {
+        let auth = auth();
+
+        for key in 0..VERIFIED_CAPACITY + 10 {
+            auth.remember(key.to_string());
+        }
+
+        assert_eq!(auth.verified.read().unwrap().len(), VERIFIED_CAPACITY);
+    }
+}
@@ -0,0 +1,85 @@
+use crate::database::BudgetData;
+use serde::Deserialize;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = budgets::ReplyItem)]
+pub struct ReplyItem {
+    id: i64,
+    category: String,
+    amount: f64,
+    spent: f64,
+    over: bool,
+}
+
+impl From<BudgetData> for ReplyItem {
+    fn from(value: BudgetData) -> Self {
+        Self {
+            id: value.node(),
+            category: value.category().into(),
+            amount: value.amount(),
+            spent: value.spent(),
+            over: value.is_over(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(untagged)]
+#[schema(as = budgets::Reply)]
+pub enum Reply {
+    List {
+        success: bool,
+        month: String,
+        items: Vec<ReplyItem>,
+    },
+    Success {
+        success: bool,
+    },
+}
+
+impl Reply {
+    pub fn list(month: &str, items: Vec<BudgetData>) -> Self {
+        let items = items.into_iter().map(ReplyItem::from).collect();
+
+        Reply::List {
+            success: true,
+            month: month.into(),
+            items,
+        }
+    }
+
+    pub fn success() -> Self {
+        Reply::Success { success: true }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = budgets::UpdateParams)]
+pub struct UpdateParams {
+    category: String,
+    amount: f64,
+}
+
+impl UpdateParams {
+    pub fn category(&self) -> &str {
+        &self.category
+    }
+
+    pub fn amount(&self) -> f64 {
+        self.amount
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = budgets::DeleteParams)]
+pub struct DeleteParams {
+    id: i64,
+}
+
+impl DeleteParams {
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+}
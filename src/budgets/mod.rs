@@ -0,0 +1,128 @@
+mod data;
+
+pub use self::data::DeleteParams;
+pub use self::data::Reply;
+pub use self::data::UpdateParams;
+
+use crate::api_error::OrReject;
+use crate::database::BudgetData;
+use crate::database::Database;
+use crate::database::UserData;
+use std::error::Error;
+use std::sync::OnceLock;
+use time::macros::format_description;
+use time::OffsetDateTime;
+use time::UtcOffset;
+use warp::Rejection;
+
+static LOCAL_OFFSET: OnceLock<UtcOffset> = OnceLock::new();
+
+/// Reads the offset of the local time zone. The offset can only be read while
+/// the process is single threaded, so it is called before the runtime starts,
+/// UTC is used when the offset is unknown.
+pub fn init_local_offset() {
+    let offset = UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC);
+
+    LOCAL_OFFSET.get_or_init(|| offset);
+}
+
+pub fn local_offset() -> UtcOffset {
+    LOCAL_OFFSET.get().copied().unwrap_or(UtcOffset::UTC)
+}
+
+/// Returns the current month in local time, ticket dates are local as well.
+pub fn current_month() -> Result<String, Box<dyn Error>> {
+    let format = format_description!("[year].[month]");
+    let now = OffsetDateTime::now_utc().to_offset(local_offset());
+
+    Ok(now.format(&format)?)
+}
+
+/// Describes budgets which were within their limit before and are exceeded
+/// after.
+pub fn overspent(before: &[BudgetData], after: &[BudgetData]) -> Vec<String> {
+    after
+        .iter()
+        .filter(|budget| budget.is_over())
+        .filter(|budget| {
+            !before
+                .iter()
+                .any(|previous| previous.node() == budget.node() && previous.is_over())
+        })
+        .map(|budget| {
+            format!(
+                "{} is over budget: spent {:.2} of {:.2}",
+                budget.category(),
+                budget.spent(),
+                budget.amount()
+            )
+        })
+        .collect()
+}
+
+/// Lists budgets with spending of the current month.
+#[utoipa::path(
+    get,
+    path = "/api/v1/budgets",
+    tag = "budgets",
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn list(user: UserData, database: Database) -> Result<impl warp::Reply, Rejection> {
+    info!("Request budgets");
+
+    let month = current_month().or_reject("Failed to format month")?;
+    let items = database
+        .select_budgets(user.owner(), &month)
+        .await
+        .or_reject("Failed to read budgets")?;
+
+    Ok(warp::reply::json(&Reply::list(&month, items)))
+}
+
+/// Creates or changes the budget of a category.
+#[utoipa::path(
+    put,
+    path = "/api/v1/budgets",
+    tag = "budgets",
+    request_body = UpdateParams,
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn update(
+    user: UserData,
+    params: UpdateParams,
+    database: Database,
+) -> Result<impl warp::Reply, Rejection> {
+    info!("Request budget update: {:?}", params);
+
+    database
+        .update_budget(user.owner(), params.category(), params.amount())
+        .await
+        .or_reject("Failed to save budget")?;
+
+    Ok(warp::reply::json(&Reply::success()))
+}
+
+/// Removes a budget.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/budgets/{id}",
+    tag = "budgets",
+    params(
+        ("id" = i64, Path, description = "Budget id"),
+    ),
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn delete(
+    user: UserData,
+    params: DeleteParams,
+    database: Database,
+) -> Result<impl warp::Reply, Rejection> {
+    info!("Request budget delete: {:?}", params);
+
+    database
+        .remove_budget(user.owner(), params.id())
+        .await
+        .or_reject("Failed to delete budget")?;
+
+    Ok(warp::reply::json(&Reply::success()))
+}
@@ -1,63 +1,177 @@
+use crate::classifier::Suggestion;
+use crate::database::CategoryNodeData;
 use crate::database::ProductData;
+use crate::database::CATEGORY_SEPARATOR;
 use serde::Deserialize;
 use serde::Serialize;
+use std::collections::HashMap;
+use utoipa::ToSchema;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = categories::ReplySuggestion)]
+pub struct ReplySuggestion {
+    category: String,
+    name: String,
+    confidence: f64,
+}
+
+impl From<Suggestion> for ReplySuggestion {
+    fn from(value: Suggestion) -> Self {
+        Self {
+            category: value.category().into(),
+            name: value.name().into(),
+            confidence: value.confidence(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = categories::ReplyItem)]
 pub struct ReplyItem {
     product: String,
+    canonical: String,
     category: String,
     name: String,
+    suggestion: Option<ReplySuggestion>,
 }
 
-impl From<ProductData> for ReplyItem {
-    fn from(value: ProductData) -> Self {
+impl ReplyItem {
+    fn new(value: ProductData, suggestion: Option<Suggestion>) -> Self {
         Self {
             product: value.product().into(),
+            canonical: value.canonical().cloned().unwrap_or_default(),
             category: value.category().cloned().unwrap_or_default(),
             name: value.name().cloned().unwrap_or_default(),
+            suggestion: suggestion.map(ReplySuggestion::from),
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = categories::ReplyNode)]
+pub struct ReplyNode {
+    id: i64,
+    parent: Option<i64>,
+    name: String,
+    path: String,
+    depth: usize,
+    sum: f64,
+    total: f64,
+}
+
+/// Orders nodes depth first and rolls up sums of every subtree into `total`.
+fn tree(nodes: Vec<CategoryNodeData>) -> Vec<ReplyNode> {
+    let mut children: HashMap<Option<i64>, Vec<&CategoryNodeData>> = HashMap::new();
+
+    for node in &nodes {
+        children.entry(node.parent()).or_default().push(node);
+    }
+
+    fn visit(
+        children: &HashMap<Option<i64>, Vec<&CategoryNodeData>>,
+        node: &CategoryNodeData,
+        path: &str,
+        depth: usize,
+        result: &mut Vec<ReplyNode>,
+    ) -> f64 {
+        let path = match path {
+            "" => node.name().to_string(),
+            path => format!("{}{}{}", path, CATEGORY_SEPARATOR, node.name()),
+        };
+        let index = result.len();
+        result.push(ReplyNode {
+            id: node.id(),
+            parent: node.parent(),
+            name: node.name().into(),
+            path: path.clone(),
+            depth,
+            sum: node.sum(),
+            total: 0.0,
+        });
+
+        let mut total = node.sum();
+
+        for child in children.get(&Some(node.id())).into_iter().flatten() {
+            total += visit(children, child, &path, depth + 1, result);
+        }
+
+        result[index].total = total;
+
+        total
+    }
+
+    let mut result = Vec::with_capacity(nodes.len());
+
+    for root in children.get(&None).into_iter().flatten() {
+        visit(&children, root, "", 0, &mut result);
+    }
+
+    result
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(untagged)]
+#[schema(as = categories::Reply)]
 pub enum Reply {
     List {
         success: bool,
         items: Vec<ReplyItem>,
     },
-    Success {
+    Tree {
+        success: bool,
+        nodes: Vec<ReplyNode>,
+    },
+    Created {
+        success: bool,
+        id: i64,
+    },
+    Renamed {
         success: bool,
+        count: usize,
     },
-    Error {
+    Success {
         success: bool,
-        message: String,
     },
 }
 
 impl Reply {
-    pub fn list(items: Vec<ProductData>) -> Self {
-        let items = items.into_iter().map(ReplyItem::from).collect();
+    pub fn list(items: Vec<(ProductData, Option<Suggestion>)>) -> Self {
+        let items = items
+            .into_iter()
+            .map(|(item, suggestion)| ReplyItem::new(item, suggestion))
+            .collect();
 
         Reply::List {
             success: true,
-            items: items,
+            items,
         }
     }
 
-    pub fn success() -> Self {
-        Reply::Success { success: true }
+    pub fn tree(nodes: Vec<CategoryNodeData>) -> Self {
+        Reply::Tree {
+            success: true,
+            nodes: tree(nodes),
+        }
     }
 
-    pub fn error(message: &str) -> Self {
-        Reply::Error {
-            success: false,
-            message: message.into(),
+    pub fn created(id: i64) -> Self {
+        Reply::Created { success: true, id }
+    }
+
+    pub fn renamed(count: usize) -> Self {
+        Reply::Renamed {
+            success: true,
+            count,
         }
     }
+
+    pub fn success() -> Self {
+        Reply::Success { success: true }
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = categories::UpdateParams)]
 pub struct UpdateParams {
     product: String,
     category: String,
@@ -77,3 +191,110 @@ impl UpdateParams {
         &self.name
     }
 }
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = categories::CreateParams)]
+pub struct CreateParams {
+    parent: Option<i64>,
+    name: String,
+}
+
+impl CreateParams {
+    pub fn parent(&self) -> Option<i64> {
+        self.parent
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.trim()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = categories::RenameParams)]
+pub struct RenameParams {
+    id: i64,
+    name: String,
+}
+
+impl RenameParams {
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.trim()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = categories::MoveParams)]
+pub struct MoveParams {
+    id: i64,
+    parent: Option<i64>,
+}
+
+impl MoveParams {
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+
+    pub fn parent(&self) -> Option<i64> {
+        self.parent
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = categories::MergeParams)]
+pub struct MergeParams {
+    source: i64,
+    target: i64,
+}
+
+impl MergeParams {
+    pub fn source(&self) -> i64 {
+        self.source
+    }
+
+    pub fn target(&self) -> i64 {
+        self.target
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = categories::AssignParams)]
+pub struct AssignParams {
+    products: Vec<String>,
+    category: String,
+    name: String,
+}
+
+impl AssignParams {
+    pub fn products(&self) -> &[String] {
+        &self.products
+    }
+
+    pub fn category(&self) -> &str {
+        &self.category
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = categories::PathParams)]
+pub struct PathParams {
+    source: String,
+    target: String,
+}
+
+impl PathParams {
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+}
@@ -1,50 +1,361 @@
 mod data;
 
+pub use self::data::AssignParams;
+pub use self::data::CreateParams;
+pub use self::data::MergeParams;
+pub use self::data::MoveParams;
+pub use self::data::PathParams;
+pub use self::data::RenameParams;
 pub use self::data::Reply;
-pub use self::data::ReplyItem;
 pub use self::data::UpdateParams;
 
+use crate::api_error::OrReject;
+use crate::classifier::Classifier;
 use crate::database::Database;
-use std::convert::Infallible;
+use crate::database::UserData;
+use warp::Rejection;
 
-macro_rules! no_fail {
-    ($message:expr, $callback:expr) => {
-        match $callback {
-            Ok(result) => result,
-            Err(error) => {
-                warn!("{}: {}", $message, error);
+/// Lists products with categories and suggestions.
+#[utoipa::path(
+    get,
+    path = "/api/v1/categories",
+    tag = "categories",
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn list(
+    user: UserData,
+    database: Database,
+    classifier: Classifier,
+) -> Result<impl warp::Reply, Rejection> {
+    info!("Request categories");
 
-                let message = format!("{}", error);
+    let products = database
+        .select_category_names(user.owner())
+        .await
+        .or_reject("Failed to read items")?;
+    let mut items = Vec::with_capacity(products.len());
 
-                return Ok(warp::reply::json(&Reply::error(&message)));
-            }
-        }
-    };
-}
-
-pub async fn list(database: Database) -> Result<impl warp::Reply, Infallible> {
-    info!("Request categories");
+    for item in products {
+        let suggestion = match item.category() {
+            Some(_) => None,
+            None => classifier.suggest(user.owner(), item.product()).await,
+        };
 
-    let items = no_fail!(
-        "Failed to read items",
-        database.select_category_names().await
-    );
+        items.push((item, suggestion));
+    }
 
     Ok(warp::reply::json(&Reply::list(items)))
 }
 
+/// Sets the category of a product, `product` is taken from the path.
+#[utoipa::path(
+    put,
+    path = "/api/v1/products/{product}/category",
+    tag = "products",
+    params(
+        ("product" = String, Path, description = "Percent-encoded product name"),
+    ),
+    request_body = UpdateParams,
+    responses((status = 200, description = "Success", body = Reply))
+)]
 pub async fn update(
+    user: UserData,
     params: UpdateParams,
     database: Database,
-) -> Result<impl warp::Reply, Infallible> {
+    classifier: Classifier,
+) -> Result<impl warp::Reply, Rejection> {
     info!("Request category update: {:?}", params);
 
-     no_fail!(
-        "Failed to save category",
-        database
-            .updatre_product_category(params.product(), params.category(), params.name())
-            .await
-    );
+    database
+        .update_product_category(
+            user.owner(),
+            params.product(),
+            params.category(),
+            params.name(),
+        )
+        .await
+        .or_reject("Failed to save category")?;
+
+    database
+        .inherit_canonical_categories(user.owner())
+        .await
+        .or_reject("Failed to inherit categories")?;
+
+    let products = database
+        .select_products(user.owner())
+        .await
+        .or_reject("Failed to read products")?;
+    classifier.train(user.owner(), &products).await;
 
     Ok(warp::reply::json(&Reply::success()))
 }
+
+/// Returns the category tree with spending.
+#[utoipa::path(
+    get,
+    path = "/api/v1/categories/tree",
+    tag = "categories",
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn tree(user: UserData, database: Database) -> Result<impl warp::Reply, Rejection> {
+    info!("Request category tree");
+
+    let nodes = database
+        .select_category_nodes(user.owner())
+        .await
+        .or_reject("Failed to read categories")?;
+
+    Ok(warp::reply::json(&Reply::tree(nodes)))
+}
+
+/// Creates a category.
+#[utoipa::path(
+    post,
+    path = "/api/v1/categories",
+    tag = "categories",
+    request_body = CreateParams,
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn create(
+    user: UserData,
+    params: CreateParams,
+    database: Database,
+) -> Result<impl warp::Reply, Rejection> {
+    info!("Request category create: {:?}", params);
+
+    let id = database
+        .insert_category_node(user.owner(), params.parent(), params.name())
+        .await
+        .or_reject("Failed to create category")?;
+
+    Ok(warp::reply::json(&Reply::created(id)))
+}
+
+/// Renames a category, `id` is taken from the path.
+#[utoipa::path(
+    put,
+    path = "/api/v1/categories/{id}/name",
+    tag = "categories",
+    params(
+        ("id" = i64, Path, description = "Category id"),
+    ),
+    request_body = RenameParams,
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn rename(
+    user: UserData,
+    params: RenameParams,
+    database: Database,
+    classifier: Classifier,
+) -> Result<impl warp::Reply, Rejection> {
+    info!("Request category rename: {:?}", params);
+
+    database
+        .rename_category_node(user.owner(), params.id(), params.name())
+        .await
+        .or_reject("Failed to rename category")?;
+
+    let products = database
+        .select_products(user.owner())
+        .await
+        .or_reject("Failed to read products")?;
+    classifier.train(user.owner(), &products).await;
+
+    Ok(warp::reply::json(&Reply::success()))
+}
+
+/// Moves a category, `id` is taken from the path.
+#[utoipa::path(
+    put,
+    path = "/api/v1/categories/{id}/parent",
+    tag = "categories",
+    params(
+        ("id" = i64, Path, description = "Category id"),
+    ),
+    request_body = MoveParams,
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn move_node(
+    user: UserData,
+    params: MoveParams,
+    database: Database,
+    classifier: Classifier,
+) -> Result<impl warp::Reply, Rejection> {
+    info!("Request category move: {:?}", params);
+
+    database
+        .move_category_node(user.owner(), params.id(), params.parent())
+        .await
+        .or_reject("Failed to move category")?;
+
+    let products = database
+        .select_products(user.owner())
+        .await
+        .or_reject("Failed to read products")?;
+    classifier.train(user.owner(), &products).await;
+
+    Ok(warp::reply::json(&Reply::success()))
+}
+
+/// Merges a category into another one, `source` is taken from the path.
+#[utoipa::path(
+    post,
+    path = "/api/v1/categories/{id}/merge",
+    tag = "categories",
+    params(
+        ("id" = i64, Path, description = "Category id"),
+    ),
+    request_body = MergeParams,
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn merge(
+    user: UserData,
+    params: MergeParams,
+    database: Database,
+    classifier: Classifier,
+) -> Result<impl warp::Reply, Rejection> {
+    info!("Request category merge: {:?}", params);
+
+    database
+        .merge_category_nodes(user.owner(), params.source(), params.target())
+        .await
+        .or_reject("Failed to merge categories")?;
+
+    let products = database
+        .select_products(user.owner())
+        .await
+        .or_reject("Failed to read products")?;
+    classifier.train(user.owner(), &products).await;
+
+    Ok(warp::reply::json(&Reply::success()))
+}
+
+/// Sets the category of several products.
+#[utoipa::path(
+    put,
+    path = "/api/v1/products/categories",
+    tag = "products",
+    request_body = AssignParams,
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn assign(
+    user: UserData,
+    params: AssignParams,
+    database: Database,
+    classifier: Classifier,
+) -> Result<impl warp::Reply, Rejection> {
+    info!("Request category assign: {:?}", params);
+
+    database
+        .update_product_categories(
+            user.owner(),
+            params.products(),
+            params.category(),
+            params.name(),
+        )
+        .await
+        .or_reject("Failed to assign category")?;
+
+    database
+        .inherit_canonical_categories(user.owner())
+        .await
+        .or_reject("Failed to inherit categories")?;
+
+    let products = database
+        .select_products(user.owner())
+        .await
+        .or_reject("Failed to read products")?;
+    classifier.train(user.owner(), &products).await;
+
+    Ok(warp::reply::json(&Reply::success()))
+}
+
+/// Renames a category path.
+#[utoipa::path(
+    post,
+    path = "/api/v1/categories/paths/rename",
+    tag = "categories",
+    request_body = PathParams,
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn rename_path(
+    user: UserData,
+    params: PathParams,
+    database: Database,
+    classifier: Classifier,
+) -> Result<impl warp::Reply, Rejection> {
+    info!("Request category path rename: {:?}", params);
+
+    database
+        .rename_category_path(user.owner(), params.source(), params.target())
+        .await
+        .or_reject("Failed to rename category")?;
+
+    let products = database
+        .select_products(user.owner())
+        .await
+        .or_reject("Failed to read products")?;
+    classifier.train(user.owner(), &products).await;
+
+    Ok(warp::reply::json(&Reply::success()))
+}
+
+/// Merges a category path into another one.
+#[utoipa::path(
+    post,
+    path = "/api/v1/categories/paths/merge",
+    tag = "categories",
+    request_body = PathParams,
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn merge_path(
+    user: UserData,
+    params: PathParams,
+    database: Database,
+    classifier: Classifier,
+) -> Result<impl warp::Reply, Rejection> {
+    info!("Request category path merge: {:?}", params);
+
+    database
+        .merge_category_paths(user.owner(), params.source(), params.target())
+        .await
+        .or_reject("Failed to merge categories")?;
+
+    let products = database
+        .select_products(user.owner())
+        .await
+        .or_reject("Failed to read products")?;
+    classifier.train(user.owner(), &products).await;
+
+    Ok(warp::reply::json(&Reply::success()))
+}
+
+/// Renames a product name in all categories.
+#[utoipa::path(
+    post,
+    path = "/api/v1/categories/names/rename",
+    tag = "categories",
+    request_body = PathParams,
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn rename_name(
+    user: UserData,
+    params: PathParams,
+    database: Database,
+    classifier: Classifier,
+) -> Result<impl warp::Reply, Rejection> {
+    info!("Request category name rename: {:?}", params);
+
+    let count = database
+        .rename_category_names(user.owner(), params.source(), params.target())
+        .await
+        .or_reject("Failed to rename name")?;
+
+    let products = database
+        .select_products(user.owner())
+        .await
+        .or_reject("Failed to read products")?;
+    classifier.train(user.owner(), &products).await;
+
+    Ok(warp::reply::json(&Reply::renamed(count)))
+}
@@ -0,0 +1,98 @@
+use rcgen::CertificateParams;
+use rcgen::DistinguishedName;
+use rcgen::DnType;
+use rcgen::KeyPair;
+use rcgen::SanType;
+use std::error::Error;
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+use std::path::Path;
+use time::Duration;
+use time::OffsetDateTime;
+
+const CERTIFICATE_LIFETIME: Duration = Duration::days(825);
+
+/// Generates a key pair and a self-signed certificate when neither file
+/// exists, so every installation gets its own certificate. The bind address,
+/// the host name and `localhost` are added as subject alternative names.
+pub fn ensure(key: &Path, certificate: &Path, address: &IpAddr) -> Result<(), Box<dyn Error>> {
+    match (key.exists(), certificate.exists()) {
+        (true, true) => return Ok(()),
+        (false, false) => {}
+        (true, false) => {
+            return Err(format!("Certificate {} not found", certificate.display()).into())
+        }
+        (false, true) => return Err(format!("Key {} not found", key.display()).into()),
+    }
+
+    info!("Generate self-signed certificate {}", certificate.display());
+
+    let key_pair = KeyPair::generate()?;
+    let cert = params(address)?.self_signed(&key_pair)?;
+
+    for path in [key, certificate] {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+    }
+
+    write_private(key, &key_pair.serialize_pem())?;
+    fs::write(certificate, cert.pem())?;
+
+    Ok(())
+}
+
+fn params(address: &IpAddr) -> Result<CertificateParams, Box<dyn Error>> {
+    let hostname = gethostname::gethostname().to_string_lossy().into_owned();
+    let mut names = vec!["localhost".to_string()];
+
+    if !hostname.is_empty() && hostname != "localhost" {
+        names.push(hostname.clone());
+    }
+
+    let mut params = CertificateParams::new(names)?;
+    let mut addresses = vec![IpAddr::V4(Ipv4Addr::LOCALHOST)];
+
+    if !address.is_unspecified() && !address.is_loopback() {
+        addresses.push(*address);
+    }
+
+    params
+        .subject_alt_names
+        .extend(addresses.into_iter().map(SanType::IpAddress));
+
+    let mut name = DistinguishedName::new();
+    name.push(DnType::CommonName, format!("Receipt Check {}", hostname));
+    params.distinguished_name = name;
+
+    let now = OffsetDateTime::now_utc();
+    params.not_before = now - Duration::days(1);
+    params.not_after = now + CERTIFICATE_LIFETIME;
+
+    Ok(params)
+}
+
+#[cfg(unix)]
+fn write_private(path: &Path, contents: &str) -> Result<(), Box<dyn Error>> {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)?;
+    file.write_all(contents.as_bytes())?;
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn write_private(path: &Path, contents: &str) -> Result<(), Box<dyn Error>> {
+    let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+    file.write_all(contents.as_bytes())?;
+
+    Ok(())
+}
@@ -0,0 +1,234 @@
+use crate::database::ProductData;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Multinomial naive Bayes model trained on manually categorized products.
+/// Every distinct category and name pair is a separate class.
+#[derive(Debug, Default)]
+struct Model {
+    documents: usize,
+    vocabulary: usize,
+    classes: Vec<Class>,
+}
+
+#[derive(Debug)]
+struct Class {
+    category: String,
+    name: String,
+    documents: usize,
+    tokens: usize,
+    counts: HashMap<String, usize>,
+}
+
+#[derive(Debug)]
+pub struct Suggestion {
+    category: String,
+    name: String,
+    confidence: f64,
+}
+
+impl Suggestion {
+    pub fn category(&self) -> &str {
+        &self.category
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn confidence(&self) -> f64 {
+        self.confidence
+    }
+}
+
+/// Keeps a separate model for every owner so suggestions are based only on
+/// the owner's own categories.
+#[derive(Debug, Clone, Default)]
+pub struct Classifier {
+    inner: Arc<RwLock<HashMap<i64, Model>>>,
+}
+
+impl Classifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn train(&self, owner: i64, products: &[ProductData]) {
+        let mut classes: HashMap<(&str, &str), Class> = HashMap::new();
+        let mut vocabulary = HashSet::new();
+        let mut documents = 0;
+
+        for product in products {
+            let (category, name) = match (product.category(), product.name()) {
+                (Some(category), Some(name)) => (category, name),
+                _ => continue,
+            };
+            let class = classes.entry((category, name)).or_insert_with(|| Class {
+                category: category.clone(),
+                name: name.clone(),
+                documents: 0,
+                tokens: 0,
+                counts: HashMap::new(),
+            });
+            class.documents += 1;
+            documents += 1;
+
+            for token in tokenize(product.product()) {
+                class.tokens += 1;
+                *class.counts.entry(token.clone()).or_default() += 1;
+                vocabulary.insert(token);
+            }
+        }
+
+        debug!(
+            "Classifier trained: owner = {}, {} products, {} classes, {} tokens",
+            owner,
+            documents,
+            classes.len(),
+            vocabulary.len()
+        );
+
+        let model = Model {
+            documents,
+            vocabulary: vocabulary.len(),
+            classes: classes.into_values().collect(),
+        };
+
+        self.inner.write().await.insert(owner, model);
+    }
+
+    /// Returns the most likely category and name of the product together with
+    /// its posterior probability.
+    pub async fn suggest(&self, owner: i64, product: &str) -> Option<Suggestion> {
+        let models = self.inner.read().await;
+        let model = models.get(&owner)?;
+
+        if model.classes.is_empty() {
+            return None;
+        }
+
+        let tokens = tokenize(product);
+        let vocabulary = model.vocabulary as f64 + 1.0;
+        let scores: Vec<f64> = model
+            .classes
+            .iter()
+            .map(|class| {
+                let prior = (class.documents as f64 / model.documents as f64).ln();
+                let total = class.tokens as f64 + vocabulary;
+
+                tokens.iter().fold(prior, |score, token| {
+                    let count = class.counts.get(token).copied().unwrap_or_default();
+
+                    score + ((count as f64 + 1.0) / total).ln()
+                })
+            })
+            .collect();
+        let (best, best_score) = scores
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))?;
+        let normalizer: f64 = scores.iter().map(|score| (score - best_score).exp()).sum();
+        let class = &model.classes[best];
+
+        Some(Suggestion {
+            category: class.category.clone(),
+            name: class.name.clone(),
+            confidence: 1.0 / normalizer,
+        })
+    }
+}
+
+/// Splits product text into lower case words, word bigrams and character
+/// trigrams, the latter helps with abbreviations common in receipts.
+fn tokenize(text: &str) -> Vec<String> {
+    let lowercase = text.to_lowercase();
+    let words: Vec<&str> = lowercase
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect();
+    let mut result = Vec::new();
+
+    for word in &words {
+        result.push(format!("w:{}", word));
+
+        let chars: Vec<char> = word.chars().collect();
+
+        for window in chars.windows(3) {
+            result.push(format!("c:{}", window.iter().collect::<String>()));
+        }
+    }
+
+    for pair in words.windows(2) {
+        result.push(format!("b:{} {}", pair[0], pair[1]));
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn product(product: &str, category: &str, name: &str) -> ProductData {
+        ProductData::new(
+            product.into(),
+            None,
+            Some(category.into()),
+            Some(name.into()),
+        )
+    }
+
+    #[test]
+    fn tokenize_words_bigrams_and_trigrams() {
+        let tokens = tokenize("МОЛОКО 3,2%");
+
+        assert_eq!(
+            tokens,
+            vec![
+                "w:молоко",
+                "c:мол",
+                "c:оло",
+                "c:лок",
+                "c:око",
+                "w:3",
+                "w:2",
+                "b:молоко 3",
+                "b:3 2",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn suggest_trained_category() {
+        let classifier = Classifier::new();
+        let products = [
+            product("МОЛОКО ПРОСТОКВАШИНО 2,5%", "Food", "Milk"),
+            product("Молоко Домик в деревне", "Food", "Milk"),
+            product("ХЛЕБ БОРОДИНСКИЙ", "Food", "Bread"),
+            product("Шампунь Head&Shoulders", "Home", "Hygiene"),
+        ];
+
+        classifier.train(1, &products).await;
+
+        let suggestion = classifier.suggest(1, "МОЛОКО ВКУСНОТЕЕВО").await.unwrap();
+
+        assert_eq!(suggestion.category(), "Food");
+        assert_eq!(suggestion.name(), "Milk");
+        assert!(suggestion.confidence() > 0.5 && suggestion.confidence() <= 1.0);
+    }
+
+    #[tokio::test]
+    async fn suggest_nothing_without_categorized_products() {
+        let classifier = Classifier::new();
+        let products = [ProductData::new("МОЛОКО".into(), None, None, None)];
+
+        assert!(classifier.suggest(1, "МОЛОКО").await.is_none());
+
+        classifier.train(1, &products).await;
+
+        assert!(classifier.suggest(1, "МОЛОКО").await.is_none());
+        assert!(classifier.suggest(2, "МОЛОКО").await.is_none());
+    }
+}
@@ -0,0 +1,108 @@
+use crate::database::TicketItemData;
+use crate::options::ExportFormat;
+use serde::Serialize;
+use std::error::Error;
+use std::io::Write;
+use time::macros::format_description;
+use time::Date;
+
+#[derive(Debug, Serialize)]
+struct ExportItem<'a> {
+    date: &'a str,
+    ticket: &'a str,
+    product: &'a str,
+    category: Option<&'a String>,
+    name: Option<&'a String>,
+    quantity: f64,
+    sum: f64,
+    unit_price: Option<f64>,
+    unit: Option<&'a String>,
+    member: Option<&'a String>,
+}
+
+impl<'a> From<&'a TicketItemData> for ExportItem<'a> {
+    fn from(value: &'a TicketItemData) -> Self {
+        Self {
+            date: value.date(),
+            ticket: value.ticket(),
+            product: value.product(),
+            category: value.category(),
+            name: value.name(),
+            quantity: value.quantity(),
+            sum: value.sum(),
+            unit_price: value.unit_price(),
+            unit: value.unit(),
+            member: value.member(),
+        }
+    }
+}
+
+/// Keeps items between the dates, both inclusive. Ticket dates are stored as
+/// `YYYY.MM.DD` strings, so they are compared as strings.
+pub fn filter(
+    items: Vec<TicketItemData>,
+    from: Option<&Date>,
+    to: Option<&Date>,
+) -> Result<Vec<TicketItemData>, Box<dyn Error>> {
+    let format = format_description!("[year].[month].[day]");
+    let from = from.map(|date| date.format(&format)).transpose()?;
+    let to = to.map(|date| date.format(&format)).transpose()?;
+
+    Ok(items
+        .into_iter()
+        .filter(|item| from.as_deref().is_none_or(|from| item.date() >= from))
+        .filter(|item| to.as_deref().is_none_or(|to| item.date() <= to))
+        .collect())
+}
+
+pub fn write<W: Write>(
+    format: ExportFormat,
+    items: &[TicketItemData],
+    mut output: W,
+) -> Result<(), Box<dyn Error>> {
+    let items: Vec<ExportItem> = items.iter().map(ExportItem::from).collect();
+
+    match format {
+        ExportFormat::Json => {
+            serde_json::to_writer_pretty(&mut output, &items)?;
+            writeln!(output)?;
+        }
+        ExportFormat::Csv => {
+            writeln!(
+                output,
+                "date,ticket,product,category,name,quantity,sum,unit_price,unit,member"
+            )?;
+
+            for item in items {
+                let fields = [
+                    csv(item.date),
+                    csv(item.ticket),
+                    csv(item.product),
+                    csv(item.category.map_or("", |value| value)),
+                    csv(item.name.map_or("", |value| value)),
+                    format!("{:.3}", item.quantity),
+                    format!("{:.2}", item.sum),
+                    item.unit_price
+                        .map(|price| format!("{:.2}", price))
+                        .unwrap_or_default(),
+                    csv(item.unit.map_or("", |value| value)),
+                    csv(item.member.map_or("", |value| value)),
+                ];
+
+                writeln!(output, "{}", fields.join(","))?;
+            }
+        }
+    }
+
+    output.flush()?;
+
+    Ok(())
+}
+
+fn csv(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
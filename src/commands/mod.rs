@@ -0,0 +1,163 @@
+mod export;
+mod report;
+
+use crate::database::Database;
+use crate::ofd::Provider;
+use crate::options::Command;
+use crate::options::Options;
+use crate::products;
+use crate::qrcode;
+use crate::rules;
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::io::Read;
+
+/// Runs a command line command other than `serve` and `config`.
+pub async fn run(
+    command: &Command,
+    options: &Options,
+    database: &Database,
+) -> Result<(), Box<dyn Error>> {
+    match command {
+        Command::Fetch { household, data } => {
+            database.check_household(*household).await?;
+
+            fetch(options, database, *household, data).await
+        }
+        Command::Import { household, file } => {
+            database.check_household(*household).await?;
+
+            let content = if file.as_os_str() == "-" {
+                let mut content = String::new();
+                io::stdin().read_to_string(&mut content)?;
+                content
+            } else {
+                fs::read_to_string(file)
+                    .map_err(|error| format!("Failed to read {}: {}", file.display(), error))?
+            };
+
+            import(options, database, *household, &content).await
+        }
+        Command::Export {
+            household,
+            format,
+            from,
+            to,
+            output,
+        } => {
+            database.check_household(*household).await?;
+
+            let items = database.select_ticket_items(*household).await?;
+            let items = export::filter(items, from.as_ref(), to.as_ref())?;
+
+            match output {
+                Some(path) => export::write(*format, &items, fs::File::create(path)?),
+                None => export::write(*format, &items, io::stdout().lock()),
+            }
+        }
+        Command::Report { household, report } => {
+            database.check_household(*household).await?;
+
+            report::print(database, *household, *report).await
+        }
+        Command::Categorize {
+            household,
+            apply_rules,
+            overwrite,
+        } => {
+            database.check_household(*household).await?;
+
+            categorize(database, *household, *apply_rules, *overwrite).await
+        }
+        Command::Assign { household } => {
+            let count = database.assign_unowned_data(*household).await?;
+
+            println!("Receipt items assigned: {}", count);
+
+            Ok(())
+        }
+        Command::Serve | Command::Config(_) => Ok(()),
+    }
+}
+
+async fn fetch(
+    options: &Options,
+    database: &Database,
+    household: i64,
+    data: &str,
+) -> Result<(), Box<dyn Error>> {
+    let provider = Provider::new(options.ofd_url(), options.ofd_timeout())?;
+
+    match qrcode::scan(database, &provider, household, None, data).await? {
+        Some(warnings) => {
+            println!("Receipt saved");
+
+            for warning in warnings {
+                println!("Budget warning: {}", warning);
+            }
+        }
+        None => println!("Receipt already exists"),
+    }
+
+    Ok(())
+}
+
+async fn import(
+    options: &Options,
+    database: &Database,
+    household: i64,
+    content: &str,
+) -> Result<(), Box<dyn Error>> {
+    let provider = Provider::new(options.ofd_url(), options.ofd_timeout())?;
+    let (mut saved, mut existing, mut failed) = (0, 0, 0);
+
+    for line in content.lines() {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        match qrcode::scan(database, &provider, household, None, line).await {
+            Ok(Some(_)) => saved += 1,
+            Ok(None) => existing += 1,
+            Err(error) => {
+                eprintln!("Failed to import {}: {}", line, error);
+                failed += 1;
+            }
+        }
+    }
+
+    println!(
+        "Receipts saved: {}, already existing: {}, failed: {}",
+        saved, existing, failed
+    );
+
+    if failed > 0 {
+        return Err(format!("{} receipts failed to import", failed).into());
+    }
+
+    Ok(())
+}
+
+async fn categorize(
+    database: &Database,
+    household: i64,
+    apply_rules: bool,
+    overwrite: bool,
+) -> Result<(), Box<dyn Error>> {
+    let products = database.select_uncanonical_products().await?;
+    products::canonicalize(database, &products).await?;
+    products::inherit_categories(database, household).await?;
+
+    println!("Products linked to canonical products: {}", products.len());
+
+    if apply_rules {
+        let count = rules::apply_rules(database, household, overwrite).await?;
+
+        println!("Products categorized by rules: {}", count);
+    }
+
+    Ok(())
+}
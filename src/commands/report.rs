@@ -0,0 +1,94 @@
+use crate::budgets;
+use crate::database::Database;
+use crate::options::ReportKind;
+use crate::products;
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::error::Error;
+
+pub async fn print(
+    database: &Database,
+    household: i64,
+    report: ReportKind,
+) -> Result<(), Box<dyn Error>> {
+    match report {
+        ReportKind::Monthly => monthly(database, household).await,
+        ReportKind::Inflation => inflation(database, household).await,
+        ReportKind::Budgets => budgets(database, household).await,
+    }
+}
+
+/// Prints receipts, items and the spent sum of every month.
+async fn monthly(database: &Database, household: i64) -> Result<(), Box<dyn Error>> {
+    let mut months: BTreeMap<String, (HashSet<String>, usize, f64)> = BTreeMap::new();
+
+    for item in database.select_ticket_items(household).await? {
+        let month = item.date().get(..7).unwrap_or(item.date()).to_string();
+        let (tickets, items, sum) = months.entry(month).or_default();
+
+        tickets.insert(item.ticket().to_string());
+        *items += 1;
+        *sum += item.sum();
+    }
+
+    println!(
+        "{:<8} {:>8} {:>6} {:>12}",
+        "Month", "Receipts", "Items", "Sum"
+    );
+
+    for (month, (tickets, items, sum)) in months {
+        println!(
+            "{:<8} {:>8} {:>6} {:>12.2}",
+            month,
+            tickets.len(),
+            items,
+            sum
+        );
+    }
+
+    Ok(())
+}
+
+async fn inflation(database: &Database, household: i64) -> Result<(), Box<dyn Error>> {
+    println!(
+        "{:<8} {:>8} {:>10} {:>8}",
+        "Month", "Index", "Cumulative", "Products"
+    );
+
+    for month in products::monthly_inflation(database, household).await? {
+        println!(
+            "{:<8} {:>8.2} {:>10.2} {:>8}",
+            month.month(),
+            month.index(),
+            month.cumulative(),
+            month.products()
+        );
+    }
+
+    Ok(())
+}
+
+async fn budgets(database: &Database, household: i64) -> Result<(), Box<dyn Error>> {
+    let month = budgets::current_month()?;
+
+    println!("Budgets {}", month);
+    println!("{:<30} {:>12} {:>12}", "Category", "Spent", "Budget");
+
+    for budget in database.select_budgets(household, &month).await? {
+        let over = if budget.spent() > budget.amount() {
+            " over"
+        } else {
+            ""
+        };
+
+        println!(
+            "{:<30} {:>12.2} {:>12.2}{}",
+            budget.category(),
+            budget.spent(),
+            budget.amount(),
+            over
+        );
+    }
+
+    Ok(())
+}
@@ -6,6 +6,10 @@ pub struct TicketItemData {
     name: Option<String>,
     quantity: f64,
     sum: f64,
+    unit: Option<String>,
+    unit_quantity: Option<f64>,
+    ticket: String,
+    member: Option<String>,
 }
 
 impl TicketItemData {
@@ -24,9 +28,27 @@ impl TicketItemData {
             name,
             quantity,
             sum,
+            unit: None,
+            unit_quantity: None,
+            ticket: String::new(),
+            member: None,
         }
     }
 
+    pub fn with_unit(mut self, unit: Option<String>, unit_quantity: Option<f64>) -> Self {
+        self.unit = unit;
+        self.unit_quantity = unit_quantity;
+        self
+    }
+
+    /// Sets the receipt of the item and the login of the member who scanned
+    /// it.
+    pub fn with_receipt(mut self, ticket: String, member: Option<String>) -> Self {
+        self.ticket = ticket;
+        self.member = member;
+        self
+    }
+
     pub fn date(&self) -> &str {
         &self.date
     }
@@ -50,19 +72,45 @@ impl TicketItemData {
     pub fn sum(&self) -> f64 {
         self.sum
     }
+
+    pub fn unit(&self) -> Option<&String> {
+        self.unit.as_ref()
+    }
+
+    pub fn ticket(&self) -> &str {
+        &self.ticket
+    }
+
+    pub fn member(&self) -> Option<&String> {
+        self.member.as_ref()
+    }
+
+    /// Price per kilogram, litre or piece if the bought amount is known.
+    pub fn unit_price(&self) -> Option<f64> {
+        self.unit_quantity
+            .filter(|quantity| *quantity > 0.0)
+            .map(|quantity| self.sum / quantity)
+    }
 }
 
 #[derive(Debug)]
 pub struct ProductData {
     product: String,
+    canonical: Option<String>,
     category: Option<String>,
     name: Option<String>,
 }
 
 impl ProductData {
-    pub fn new(product: String, category: Option<String>, name: Option<String>) -> Self {
+    pub fn new(
+        product: String,
+        canonical: Option<String>,
+        category: Option<String>,
+        name: Option<String>,
+    ) -> Self {
         Self {
             product,
+            canonical,
             category,
             name,
         }
@@ -72,6 +120,10 @@ impl ProductData {
         &self.product
     }
 
+    pub fn canonical(&self) -> Option<&String> {
+        self.canonical.as_ref()
+    }
+
     pub fn category(&self) -> Option<&String> {
         self.category.as_ref()
     }
@@ -80,3 +132,762 @@ impl ProductData {
         self.name.as_ref()
     }
 }
+
+#[derive(Debug)]
+pub struct RuleData {
+    id: i64,
+    priority: i64,
+    kind: String,
+    pattern: String,
+    inn: Option<String>,
+    category: String,
+    name: String,
+}
+
+impl RuleData {
+    pub fn new(
+        id: i64,
+        priority: i64,
+        kind: String,
+        pattern: String,
+        inn: Option<String>,
+        category: String,
+        name: String,
+    ) -> Self {
+        Self {
+            id,
+            priority,
+            kind,
+            pattern,
+            inn,
+            category,
+            name,
+        }
+    }
+
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+
+    pub fn priority(&self) -> i64 {
+        self.priority
+    }
+
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    pub fn inn(&self) -> Option<&String> {
+        self.inn.as_ref()
+    }
+
+    pub fn category(&self) -> &str {
+        &self.category
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[derive(Debug)]
+pub struct CategoryNodeData {
+    id: i64,
+    parent: Option<i64>,
+    name: String,
+    sum: f64,
+}
+
+impl CategoryNodeData {
+    pub fn new(id: i64, parent: Option<i64>, name: String, sum: f64) -> Self {
+        Self {
+            id,
+            parent,
+            name,
+            sum,
+        }
+    }
+
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+
+    pub fn parent(&self) -> Option<i64> {
+        self.parent
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn sum(&self) -> f64 {
+        self.sum
+    }
+}
+
+#[derive(Debug)]
+pub struct CanonicalProductData {
+    id: i64,
+    title: String,
+    amount: Option<f64>,
+    unit: Option<String>,
+    fat: Option<f64>,
+    products: Vec<ProductData>,
+}
+
+impl CanonicalProductData {
+    pub fn new(
+        id: i64,
+        title: String,
+        amount: Option<f64>,
+        unit: Option<String>,
+        fat: Option<f64>,
+    ) -> Self {
+        Self {
+            id,
+            title,
+            amount,
+            unit,
+            fat,
+            products: Vec::new(),
+        }
+    }
+
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn amount(&self) -> Option<f64> {
+        self.amount
+    }
+
+    pub fn unit(&self) -> Option<&String> {
+        self.unit.as_ref()
+    }
+
+    pub fn fat(&self) -> Option<f64> {
+        self.fat
+    }
+
+    pub fn products(&self) -> &[ProductData] {
+        &self.products
+    }
+
+    pub fn push_product(&mut self, product: ProductData) {
+        self.products.push(product);
+    }
+}
+
+#[derive(Debug)]
+pub struct PriceData {
+    date: String,
+    product: String,
+    seller: Option<String>,
+    inn: Option<String>,
+    address: Option<String>,
+    unit: Option<String>,
+    unit_quantity: Option<f64>,
+    sum: f64,
+}
+
+impl PriceData {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        date: String,
+        product: String,
+        seller: Option<String>,
+        inn: Option<String>,
+        address: Option<String>,
+        unit: Option<String>,
+        unit_quantity: Option<f64>,
+        sum: f64,
+    ) -> Self {
+        Self {
+            date,
+            product,
+            seller,
+            inn,
+            address,
+            unit,
+            unit_quantity,
+            sum,
+        }
+    }
+
+    pub fn date(&self) -> &str {
+        &self.date
+    }
+
+    pub fn product(&self) -> &str {
+        &self.product
+    }
+
+    pub fn seller(&self) -> Option<&String> {
+        self.seller.as_ref()
+    }
+
+    pub fn inn(&self) -> Option<&String> {
+        self.inn.as_ref()
+    }
+
+    pub fn address(&self) -> Option<&String> {
+        self.address.as_ref()
+    }
+
+    pub fn unit(&self) -> Option<&String> {
+        self.unit.as_ref()
+    }
+
+    pub fn sum(&self) -> f64 {
+        self.sum
+    }
+
+    pub fn unit_price(&self) -> Option<f64> {
+        self.unit_quantity
+            .filter(|quantity| *quantity > 0.0)
+            .map(|quantity| self.sum / quantity)
+    }
+}
+
+#[derive(Debug)]
+pub struct MonthlyPriceData {
+    canonical: i64,
+    unit: String,
+    month: String,
+    sum: f64,
+    unit_quantity: f64,
+    purchases: usize,
+}
+
+impl MonthlyPriceData {
+    pub fn new(
+        canonical: i64,
+        unit: String,
+        month: String,
+        sum: f64,
+        unit_quantity: f64,
+        purchases: usize,
+    ) -> Self {
+        Self {
+            canonical,
+            unit,
+            month,
+            sum,
+            unit_quantity,
+            purchases,
+        }
+    }
+
+    pub fn canonical(&self) -> i64 {
+        self.canonical
+    }
+
+    /// Unit of `unit_price`, prices of one product in different units are
+    /// not comparable.
+    pub fn unit(&self) -> &str {
+        &self.unit
+    }
+
+    pub fn month(&self) -> &str {
+        &self.month
+    }
+
+    pub fn purchases(&self) -> usize {
+        self.purchases
+    }
+
+    pub fn unit_price(&self) -> f64 {
+        self.sum / self.unit_quantity
+    }
+}
+
+#[derive(Debug)]
+pub struct StorePriceData {
+    canonical: i64,
+    title: String,
+    inn: Option<String>,
+    store: String,
+    seller: Option<String>,
+    date: String,
+    unit: String,
+    unit_quantity: f64,
+    sum: f64,
+}
+
+impl StorePriceData {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        canonical: i64,
+        title: String,
+        inn: Option<String>,
+        store: String,
+        seller: Option<String>,
+        date: String,
+        unit: String,
+        unit_quantity: f64,
+        sum: f64,
+    ) -> Self {
+        Self {
+            canonical,
+            title,
+            inn,
+            store,
+            seller,
+            date,
+            unit,
+            unit_quantity,
+            sum,
+        }
+    }
+
+    pub fn canonical(&self) -> i64 {
+        self.canonical
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn inn(&self) -> Option<&String> {
+        self.inn.as_ref()
+    }
+
+    /// Address of the store, or the seller INN when the address is missing.
+    pub fn store(&self) -> &str {
+        &self.store
+    }
+
+    pub fn seller(&self) -> Option<&String> {
+        self.seller.as_ref()
+    }
+
+    pub fn date(&self) -> &str {
+        &self.date
+    }
+
+    pub fn unit(&self) -> &str {
+        &self.unit
+    }
+
+    pub fn unit_quantity(&self) -> f64 {
+        self.unit_quantity
+    }
+
+    pub fn sum(&self) -> f64 {
+        self.sum
+    }
+}
+
+#[derive(Debug)]
+pub struct BudgetData {
+    node: i64,
+    category: String,
+    amount: f64,
+    spent: f64,
+}
+
+impl BudgetData {
+    pub fn new(node: i64, category: String, amount: f64, spent: f64) -> Self {
+        Self {
+            node,
+            category,
+            amount,
+            spent,
+        }
+    }
+
+    pub fn node(&self) -> i64 {
+        self.node
+    }
+
+    pub fn category(&self) -> &str {
+        &self.category
+    }
+
+    pub fn amount(&self) -> f64 {
+        self.amount
+    }
+
+    pub fn spent(&self) -> f64 {
+        self.spent
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.spent > self.amount
+    }
+}
+
+/// Role of a household member allowed to change shared data.
+pub const ROLE_ADMIN: &str = "admin";
+/// Role of a household member allowed to scan and view receipts.
+pub const ROLE_MEMBER: &str = "member";
+
+/// Scope of an API token allowed to do everything its user can do.
+pub const SCOPE_FULL: &str = "full";
+/// Scope of an API token allowed to read data only.
+pub const SCOPE_READ: &str = "read";
+/// Scope of an API token allowed to scan receipts only.
+pub const SCOPE_SCAN: &str = "scan";
+
+#[derive(Debug, Clone)]
+pub struct UserData {
+    id: i64,
+    login: String,
+    household: i64,
+    role: String,
+}
+
+impl UserData {
+    pub fn new(id: i64, login: String, household: i64, role: String) -> Self {
+        Self {
+            id,
+            login,
+            household,
+            role,
+        }
+    }
+
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+
+    pub fn login(&self) -> &str {
+        &self.login
+    }
+
+    pub fn household(&self) -> i64 {
+        self.household
+    }
+
+    pub fn role(&self) -> &str {
+        &self.role
+    }
+
+    pub fn is_admin(&self) -> bool {
+        self.role == ROLE_ADMIN
+    }
+
+    /// Identifies receipts, products and categories shared by the household
+    /// of the user.
+    pub fn owner(&self) -> i64 {
+        self.household
+    }
+}
+
+#[derive(Debug)]
+pub struct TokenData {
+    id: i64,
+    name: String,
+    scope: String,
+    created: i64,
+    used: Option<i64>,
+}
+
+impl TokenData {
+    pub fn new(id: i64, name: String, scope: String, created: i64, used: Option<i64>) -> Self {
+        Self {
+            id,
+            name,
+            scope,
+            created,
+            used,
+        }
+    }
+
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn scope(&self) -> &str {
+        &self.scope
+    }
+
+    pub fn created(&self) -> i64 {
+        self.created
+    }
+
+    pub fn used(&self) -> Option<i64> {
+        self.used
+    }
+}
+
+#[derive(Debug)]
+pub struct PersonData {
+    id: i64,
+    name: String,
+}
+
+impl PersonData {
+    pub fn new(id: i64, name: String) -> Self {
+        Self { id, name }
+    }
+
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[derive(Debug)]
+pub struct SplitItemData {
+    id: i64,
+    product: String,
+    sum: f64,
+    shares: Vec<(i64, f64)>,
+}
+
+impl SplitItemData {
+    pub fn new(id: i64, product: String, sum: f64) -> Self {
+        Self {
+            id,
+            product,
+            sum,
+            shares: Vec::new(),
+        }
+    }
+
+    pub fn push_share(&mut self, person: i64, share: f64) {
+        self.shares.push((person, share));
+    }
+
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+
+    pub fn product(&self) -> &str {
+        &self.product
+    }
+
+    pub fn sum(&self) -> f64 {
+        self.sum
+    }
+
+    /// Persons sharing the item with their relative shares.
+    pub fn shares(&self) -> &[(i64, f64)] {
+        &self.shares
+    }
+}
+
+#[derive(Debug)]
+pub struct SplitReceiptData {
+    ticket: String,
+    date: String,
+    seller: Option<String>,
+    payer: Option<i64>,
+    items: Vec<SplitItemData>,
+}
+
+impl SplitReceiptData {
+    pub fn new(ticket: String, date: String, seller: Option<String>, payer: Option<i64>) -> Self {
+        Self {
+            ticket,
+            date,
+            seller,
+            payer,
+            items: Vec::new(),
+        }
+    }
+
+    pub fn push_item(&mut self, item: SplitItemData) {
+        self.items.push(item);
+    }
+
+    pub fn ticket(&self) -> &str {
+        &self.ticket
+    }
+
+    pub fn date(&self) -> &str {
+        &self.date
+    }
+
+    pub fn seller(&self) -> Option<&String> {
+        self.seller.as_ref()
+    }
+
+    pub fn payer(&self) -> Option<i64> {
+        self.payer
+    }
+
+    pub fn items(&self) -> &[SplitItemData] {
+        &self.items
+    }
+
+    pub fn last_item_mut(&mut self) -> Option<&mut SplitItemData> {
+        self.items.last_mut()
+    }
+}
+
+/// Receipt header with its items, fields unknown for receipts scanned before
+/// they were stored are `None`.
+#[derive(Debug)]
+pub struct ReceiptData {
+    ticket: String,
+    time: Option<String>,
+    inn: Option<String>,
+    seller: Option<String>,
+    address: Option<String>,
+    total: Option<f64>,
+    cash: Option<f64>,
+    card: Option<f64>,
+    fiscal_drive: Option<String>,
+    fiscal_document: Option<String>,
+    fiscal_sign: Option<String>,
+    register: Option<String>,
+    scanned: Option<i64>,
+    member: Option<String>,
+    items: Vec<TicketItemData>,
+}
+
+impl ReceiptData {
+    pub fn new(ticket: String, time: Option<String>) -> Self {
+        Self {
+            ticket,
+            time,
+            inn: None,
+            seller: None,
+            address: None,
+            total: None,
+            cash: None,
+            card: None,
+            fiscal_drive: None,
+            fiscal_document: None,
+            fiscal_sign: None,
+            register: None,
+            scanned: None,
+            member: None,
+            items: Vec::new(),
+        }
+    }
+
+    pub fn with_store(
+        mut self,
+        inn: Option<String>,
+        seller: Option<String>,
+        address: Option<String>,
+    ) -> Self {
+        self.inn = inn;
+        self.seller = seller;
+        self.address = address;
+        self
+    }
+
+    /// Sets the total and its parts paid in cash and by card.
+    pub fn with_payment(
+        mut self,
+        total: Option<f64>,
+        cash: Option<f64>,
+        card: Option<f64>,
+    ) -> Self {
+        self.total = total;
+        self.cash = cash;
+        self.card = card;
+        self
+    }
+
+    /// Sets the fiscal drive, document and sign numbers printed on the
+    /// receipt and the registration number of the cash register.
+    pub fn with_fiscal(
+        mut self,
+        drive: Option<String>,
+        document: Option<String>,
+        sign: Option<String>,
+        register: Option<String>,
+    ) -> Self {
+        self.fiscal_drive = drive;
+        self.fiscal_document = document;
+        self.fiscal_sign = sign;
+        self.register = register;
+        self
+    }
+
+    /// Sets the Unix time of the scan and the login of the member who
+    /// scanned the receipt.
+    pub fn with_scan(mut self, scanned: Option<i64>, member: Option<String>) -> Self {
+        self.scanned = scanned;
+        self.member = member;
+        self
+    }
+
+    pub fn push_item(&mut self, item: TicketItemData) {
+        self.items.push(item);
+    }
+
+    pub fn ticket(&self) -> &str {
+        &self.ticket
+    }
+
+    pub fn time(&self) -> Option<&str> {
+        self.time.as_deref()
+    }
+
+    pub fn inn(&self) -> Option<&str> {
+        self.inn.as_deref()
+    }
+
+    pub fn seller(&self) -> Option<&str> {
+        self.seller.as_deref()
+    }
+
+    pub fn address(&self) -> Option<&str> {
+        self.address.as_deref()
+    }
+
+    pub fn total(&self) -> Option<f64> {
+        self.total
+    }
+
+    pub fn cash(&self) -> Option<f64> {
+        self.cash
+    }
+
+    pub fn card(&self) -> Option<f64> {
+        self.card
+    }
+
+    pub fn fiscal_drive(&self) -> Option<&str> {
+        self.fiscal_drive.as_deref()
+    }
+
+    pub fn fiscal_document(&self) -> Option<&str> {
+        self.fiscal_document.as_deref()
+    }
+
+    pub fn fiscal_sign(&self) -> Option<&str> {
+        self.fiscal_sign.as_deref()
+    }
+
+    pub fn register(&self) -> Option<&str> {
+        self.register.as_deref()
+    }
+
+    pub fn scanned(&self) -> Option<i64> {
+        self.scanned
+    }
+
+    pub fn member(&self) -> Option<&str> {
+        self.member.as_deref()
+    }
+
+    pub fn items(&self) -> &[TicketItemData] {
+        &self.items
+    }
+
+    pub fn into_items(self) -> Vec<TicketItemData> {
+        self.items
+    }
+}
@@ -0,0 +1,18 @@
+CREATE TABLE receipts (
+    ticket TEXT NOT NULL,
+    inn TEXT,
+    seller TEXT,
+    address TEXT
+);
+CREATE UNIQUE INDEX receipts_ticket ON receipts ( ticket );
+CREATE INDEX receipts_inn ON receipts ( inn );
+
+CREATE TABLE rules (
+    id INTEGER PRIMARY KEY,
+    priority INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    pattern TEXT NOT NULL,
+    inn TEXT,
+    category TEXT NOT NULL,
+    name TEXT NOT NULL
+);
@@ -0,0 +1,42 @@
+CREATE TABLE category_nodes (
+    id INTEGER PRIMARY KEY,
+    parent INTEGER REFERENCES category_nodes ( id ),
+    name TEXT NOT NULL
+);
+CREATE UNIQUE INDEX category_nodes_parent_name ON category_nodes ( IFNULL(parent, 0), name );
+
+INSERT INTO category_nodes (parent, name)
+SELECT DISTINCT NULL, category FROM products;
+
+INSERT INTO category_nodes (parent, name)
+SELECT DISTINCT c.id, p.name
+FROM products AS p INNER JOIN category_nodes AS c ON (c.parent IS NULL AND c.name = p.category);
+
+CREATE TABLE product_nodes (
+    product TEXT NOT NULL,
+    node INTEGER NOT NULL REFERENCES category_nodes ( id )
+);
+
+INSERT INTO product_nodes (product, node)
+SELECT p.product, n.id
+FROM products AS p
+    INNER JOIN category_nodes AS c ON (c.parent IS NULL AND c.name = p.category)
+    INNER JOIN category_nodes AS n ON (n.parent = c.id AND n.name = p.name);
+
+DROP TABLE products;
+ALTER TABLE product_nodes RENAME TO products;
+CREATE UNIQUE INDEX products_product ON products ( product );
+CREATE INDEX products_node ON products ( node );
+
+CREATE VIEW category_paths AS
+WITH RECURSIVE paths (id, category, name) AS (
+    SELECT id, '', name FROM category_nodes WHERE parent IS NULL
+    UNION ALL
+    SELECT n.id, CASE WHEN p.category = '' THEN p.name ELSE p.category || ' / ' || p.name END, n.name
+    FROM category_nodes AS n INNER JOIN paths AS p ON (n.parent = p.id)
+)
+SELECT id, category, name FROM paths;
+
+CREATE VIEW product_categories AS
+SELECT p.product, c.category, c.name, p.node
+FROM products AS p INNER JOIN category_paths AS c ON (c.id = p.node);
@@ -0,0 +1,16 @@
+CREATE TABLE canonical_products (
+    id INTEGER PRIMARY KEY,
+    key TEXT NOT NULL,
+    title TEXT NOT NULL,
+    amount REAL,
+    unit TEXT,
+    fat REAL
+);
+CREATE UNIQUE INDEX canonical_products_key ON canonical_products ( key );
+
+CREATE TABLE product_canonicals (
+    product TEXT NOT NULL,
+    canonical INTEGER NOT NULL REFERENCES canonical_products ( id )
+);
+CREATE UNIQUE INDEX product_canonicals_product ON product_canonicals ( product );
+CREATE INDEX product_canonicals_canonical ON product_canonicals ( canonical );
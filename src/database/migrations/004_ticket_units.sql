@@ -0,0 +1,3 @@
+ALTER TABLE tickets ADD COLUMN price REAL;
+ALTER TABLE tickets ADD COLUMN unit TEXT;
+ALTER TABLE tickets ADD COLUMN unit_quantity REAL;
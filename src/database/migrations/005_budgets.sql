@@ -0,0 +1,5 @@
+CREATE TABLE budgets (
+    node INTEGER NOT NULL REFERENCES category_nodes ( id ),
+    amount REAL NOT NULL
+);
+CREATE UNIQUE INDEX budgets_node ON budgets ( node );
@@ -0,0 +1,37 @@
+CREATE TABLE users (
+    id INTEGER PRIMARY KEY,
+    login TEXT NOT NULL,
+    password TEXT NOT NULL
+);
+CREATE UNIQUE INDEX users_login ON users ( login );
+
+CREATE TABLE sessions (
+    token TEXT NOT NULL,
+    user INTEGER NOT NULL REFERENCES users ( id ),
+    expires INTEGER NOT NULL
+);
+CREATE UNIQUE INDEX sessions_token ON sessions ( token );
+
+-- Data stored before accounts were introduced gets owner 0 until it is assigned to a user.
+ALTER TABLE tickets ADD COLUMN owner INTEGER NOT NULL DEFAULT 0;
+CREATE INDEX tickets_owner ON tickets ( owner );
+
+ALTER TABLE receipts ADD COLUMN owner INTEGER NOT NULL DEFAULT 0;
+DROP INDEX receipts_ticket;
+CREATE UNIQUE INDEX receipts_ticket ON receipts ( owner, ticket );
+
+ALTER TABLE products ADD COLUMN owner INTEGER NOT NULL DEFAULT 0;
+DROP INDEX products_product;
+CREATE UNIQUE INDEX products_product ON products ( owner, product );
+
+ALTER TABLE category_nodes ADD COLUMN owner INTEGER NOT NULL DEFAULT 0;
+DROP INDEX category_nodes_parent_name;
+CREATE UNIQUE INDEX category_nodes_parent_name ON category_nodes ( owner, IFNULL(parent, 0), name );
+
+ALTER TABLE rules ADD COLUMN owner INTEGER NOT NULL DEFAULT 0;
+CREATE INDEX rules_owner ON rules ( owner );
+
+DROP VIEW product_categories;
+CREATE VIEW product_categories AS
+SELECT p.owner, p.product, c.category, c.name, p.node
+FROM products AS p INNER JOIN category_paths AS c ON (c.id = p.node);
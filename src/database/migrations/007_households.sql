@@ -0,0 +1,14 @@
+CREATE TABLE households (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL
+);
+
+INSERT INTO households (id, name)
+SELECT id, login FROM users;
+
+ALTER TABLE users ADD COLUMN household INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE users ADD COLUMN role TEXT NOT NULL DEFAULT 'admin';
+UPDATE users SET household = id;
+CREATE INDEX users_household ON users ( household );
+
+ALTER TABLE receipts ADD COLUMN user INTEGER REFERENCES users ( id );
@@ -0,0 +1,11 @@
+CREATE TABLE tokens (
+    id INTEGER PRIMARY KEY,
+    user INTEGER NOT NULL REFERENCES users ( id ),
+    name TEXT NOT NULL,
+    token TEXT NOT NULL,
+    scope TEXT NOT NULL,
+    created INTEGER NOT NULL,
+    used INTEGER
+);
+CREATE UNIQUE INDEX tokens_token ON tokens ( token );
+CREATE INDEX tokens_user ON tokens ( user );
@@ -0,0 +1,44 @@
+-- Splits reference receipt items by id, so tickets get an explicit primary key.
+CREATE TABLE tickets_new (
+    id INTEGER PRIMARY KEY,
+    owner INTEGER NOT NULL DEFAULT 0,
+    ticket TEXT NOT NULL,
+    date TEXT NOT NULL,
+    product TEXT NOT NULL,
+    quantity REAL NOT NULL,
+    sum REAL NOT NULL,
+    price REAL,
+    unit TEXT,
+    unit_quantity REAL
+);
+
+INSERT INTO tickets_new (id, owner, ticket, date, product, quantity, sum, price, unit, unit_quantity)
+SELECT rowid, owner, ticket, date, product, quantity, sum, price, unit, unit_quantity FROM tickets;
+
+DROP TABLE tickets;
+ALTER TABLE tickets_new RENAME TO tickets;
+CREATE INDEX tickets_ticket_product ON tickets ( ticket, product );
+CREATE INDEX tickets_product ON tickets ( product );
+CREATE INDEX tickets_owner ON tickets ( owner );
+
+CREATE TABLE people (
+    id INTEGER PRIMARY KEY,
+    owner INTEGER NOT NULL,
+    name TEXT NOT NULL
+);
+CREATE UNIQUE INDEX people_owner_name ON people ( owner, name );
+
+CREATE TABLE splits (
+    item INTEGER NOT NULL REFERENCES tickets ( id ),
+    person INTEGER NOT NULL REFERENCES people ( id ),
+    share REAL NOT NULL
+);
+CREATE UNIQUE INDEX splits_item_person ON splits ( item, person );
+CREATE INDEX splits_person ON splits ( person );
+
+CREATE TABLE payments (
+    owner INTEGER NOT NULL,
+    ticket TEXT NOT NULL,
+    person INTEGER NOT NULL REFERENCES people ( id )
+);
+CREATE UNIQUE INDEX payments_owner_ticket ON payments ( owner, ticket );
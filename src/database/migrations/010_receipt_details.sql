@@ -0,0 +1,9 @@
+ALTER TABLE receipts ADD COLUMN time TEXT;
+ALTER TABLE receipts ADD COLUMN total REAL;
+ALTER TABLE receipts ADD COLUMN cash REAL;
+ALTER TABLE receipts ADD COLUMN card REAL;
+ALTER TABLE receipts ADD COLUMN fiscal_drive TEXT;
+ALTER TABLE receipts ADD COLUMN fiscal_document TEXT;
+ALTER TABLE receipts ADD COLUMN fiscal_sign TEXT;
+ALTER TABLE receipts ADD COLUMN register TEXT;
+ALTER TABLE receipts ADD COLUMN scanned INTEGER;
@@ -1,49 +1,137 @@
 mod data;
 
+pub use self::data::BudgetData;
+pub use self::data::CanonicalProductData;
+pub use self::data::CategoryNodeData;
+pub use self::data::MonthlyPriceData;
+pub use self::data::PersonData;
+pub use self::data::PriceData;
 pub use self::data::ProductData;
+pub use self::data::ReceiptData;
+pub use self::data::RuleData;
+pub use self::data::SplitItemData;
+pub use self::data::SplitReceiptData;
+pub use self::data::StorePriceData;
 pub use self::data::TicketItemData;
+pub use self::data::TokenData;
+pub use self::data::UserData;
+pub use self::data::ROLE_ADMIN;
+pub use self::data::ROLE_MEMBER;
+pub use self::data::SCOPE_FULL;
+pub use self::data::SCOPE_READ;
+pub use self::data::SCOPE_SCAN;
 
+use crate::error::Error;
 use sqlite::Connection;
 use sqlite::State;
-use std::error::Error;
 use std::path::Path;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
+/// Separates node names in the category path of a product.
+pub const CATEGORY_SEPARATOR: &str = " / ";
+
+/// Owner of data stored before accounts were introduced, it is assigned to a
+/// household explicitly with [`Database::assign_unowned_data`].
+pub const UNASSIGNED_OWNER: i64 = 0;
+
+/// Tables of household data with the `owner` column.
+const OWNED_TABLES: &[&str] = &[
+    "tickets",
+    "receipts",
+    "products",
+    "category_nodes",
+    "rules",
+    "people",
+    "payments",
+];
+
+const MIGRATIONS: &[&str] = &[
+    include_str!("migrations/001_rules.sql"),
+    include_str!("migrations/002_category_tree.sql"),
+    include_str!("migrations/003_canonical_products.sql"),
+    include_str!("migrations/004_ticket_units.sql"),
+    include_str!("migrations/005_budgets.sql"),
+    include_str!("migrations/006_users.sql"),
+    include_str!("migrations/007_households.sql"),
+    include_str!("migrations/008_tokens.sql"),
+    include_str!("migrations/009_splits.sql"),
+    include_str!("migrations/010_receipt_details.sql"),
+];
+
+/// SQLite database of receipts, products and users. Clones share the same
+/// connection, queries of user data take the owning household id first.
 #[derive(Clone)]
 pub struct Database {
     inner: Arc<Mutex<Connection>>,
 }
 
 impl Database {
-    pub fn new<P>(path: P) -> Result<Self, Box<dyn Error>>
+    /// Opens or creates the database and applies pending migrations.
+    pub fn new<P>(path: P) -> Result<Self, Error>
     where
         P: AsRef<Path>,
     {
         let connection = sqlite::open(path)?;
         connection.execute(include_str!("create_database.sql"))?;
+        migrate(&connection)?;
 
         Ok(Self {
             inner: Arc::new(Mutex::new(connection)),
         })
     }
 
-    pub async fn select_category_names(&self) -> Result<Vec<ProductData>, Box<dyn Error>> {
-        debug!("Category names");
+    pub async fn select_category_names(&self, owner: i64) -> Result<Vec<ProductData>, Error> {
+        debug!("Category names: {}", owner);
 
         let lock = self.inner.lock().await;
         let mut query = lock.prepare(
-            "SELECT DISTINCT t.product, p.category, p.name
-            FROM tickets AS t LEFT OUTER JOIN products AS p ON (p.product = t.product)
+            "SELECT DISTINCT t.product, c.title, p.category, p.name
+            FROM tickets AS t
+                LEFT OUTER JOIN product_categories AS p ON (p.owner = t.owner AND p.product = t.product)
+                LEFT OUTER JOIN product_canonicals AS pc ON (pc.product = t.product)
+                LEFT OUTER JOIN canonical_products AS c ON (c.id = pc.canonical)
+            WHERE t.owner = :owner
             ORDER BY t.product",
         )?;
+        query.bind((":owner", owner))?;
+
         let mut result = Vec::new();
 
         while let State::Row = query.next()? {
             let product = query.read(0)?;
-            let category = query.read(1)?;
-            let name = query.read(2)?;
-            let item = ProductData::new(product, category, name);
+            let canonical = query.read(1)?;
+            let category = query.read(2)?;
+            let name = query.read(3)?;
+            let item = ProductData::new(product, canonical, category, name);
+
+            result.push(item);
+        }
+
+        Ok(result)
+    }
+
+    pub async fn select_products(&self, owner: i64) -> Result<Vec<ProductData>, Error> {
+        debug!("Products: {}", owner);
+
+        let lock = self.inner.lock().await;
+        let mut query = lock.prepare(
+            "SELECT p.product, c.title, p.category, p.name
+            FROM product_categories AS p
+                LEFT OUTER JOIN product_canonicals AS pc ON (pc.product = p.product)
+                LEFT OUTER JOIN canonical_products AS c ON (c.id = pc.canonical)
+            WHERE p.owner = :owner",
+        )?;
+        query.bind((":owner", owner))?;
+
+        let mut result = Vec::new();
+
+        while let State::Row = query.next()? {
+            let product = query.read(0)?;
+            let canonical = query.read(1)?;
+            let category = query.read(2)?;
+            let name = query.read(3)?;
+            let item = ProductData::new(product, canonical, category, name);
 
             result.push(item);
         }
@@ -51,33 +139,235 @@ impl Database {
         Ok(result)
     }
 
-    pub async fn updatre_product_category(
+    pub async fn select_product_owners(&self) -> Result<Vec<i64>, Error> {
+        debug!("Product owners");
+
+        let lock = self.inner.lock().await;
+        let mut query = lock.prepare("SELECT DISTINCT owner FROM products")?;
+        let mut result = Vec::new();
+
+        while let State::Row = query.next()? {
+            result.push(query.read(0)?);
+        }
+
+        Ok(result)
+    }
+
+    pub async fn update_product_category(
         &self,
+        owner: i64,
         product: &str,
         category: &str,
         name: &str,
-    ) -> Result<(), Box<dyn Error>> {
+    ) -> Result<(), Error> {
         debug!(
             "Set category name: product = {}, category = {}, name = {}",
             product, category, name
         );
 
         let lock = self.inner.lock().await;
-        let mut query =
-            lock.prepare("INSERT OR REPLACE INTO products (category, name, product) VALUES (:category, :name, :product)")?;
-        query.bind((":product", product))?;
-        query.bind((":category", category))?;
-        query.bind((":name", name))?;
-        query.next()?;
 
-        Ok(())
+        transaction(&lock, |connection| {
+            let mut path = split_category_path(category);
+            path.push(name);
+
+            let node = resolve_category_path(connection, owner, &path)?;
+
+            insert_product_node(connection, owner, product, node)
+        })
+    }
+
+    /// Sets the category of every `(product, category, name)` change in one
+    /// transaction, so a failed change leaves all categories as they were.
+    pub async fn update_product_category_changes(
+        &self,
+        owner: i64,
+        changes: &[(&str, &str, &str)],
+    ) -> Result<(), Error> {
+        debug!("Set category names: {:?}", changes);
+
+        let lock = self.inner.lock().await;
+
+        transaction(&lock, |connection| {
+            for (product, category, name) in changes {
+                let mut path = split_category_path(category);
+                path.push(name);
+
+                let node = resolve_category_path(connection, owner, &path)?;
+
+                insert_product_node(connection, owner, product, node)?;
+            }
+
+            Ok(())
+        })
+    }
+
+    pub async fn update_product_categories(
+        &self,
+        owner: i64,
+        products: &[String],
+        category: &str,
+        name: &str,
+    ) -> Result<(), Error> {
+        debug!(
+            "Set category name: products = {:?}, category = {}, name = {}",
+            products, category, name
+        );
+
+        let lock = self.inner.lock().await;
+
+        transaction(&lock, |connection| {
+            let mut path = split_category_path(category);
+            path.push(name);
+
+            let node = resolve_category_path(connection, owner, &path)?;
+
+            for product in products {
+                insert_product_node(connection, owner, product, node)?;
+            }
+
+            Ok(())
+        })
+    }
+
+    pub async fn rename_category_path(
+        &self,
+        owner: i64,
+        category: &str,
+        new_category: &str,
+    ) -> Result<(), Error> {
+        debug!(
+            "Rename category path: category = {}, new category = {}",
+            category, new_category
+        );
+
+        let lock = self.inner.lock().await;
+
+        transaction(&lock, |connection| {
+            let source = find_category_path(connection, owner, &split_category_path(category))?;
+            let mut path = split_category_path(new_category);
+            let name = path
+                .pop()
+                .ok_or_else(|| Error::Invalid("Empty category path".into()))?;
+            let parent = match path.is_empty() {
+                true => None,
+                false => Some(resolve_category_path(connection, owner, &path)?),
+            };
+
+            if let Some(parent) = parent {
+                if is_category_descendant(connection, owner, parent, source)? {
+                    return Err(Error::Invalid(
+                        "Category can not be moved into itself".into(),
+                    ));
+                }
+            }
+
+            match find_category_node(connection, owner, parent, name)? {
+                Some(target) if target == source => Ok(()),
+                Some(target) => merge_category_nodes(connection, owner, source, target),
+                None => {
+                    validate_category_name(name)?;
+
+                    let mut query = connection.prepare(
+                        "UPDATE category_nodes SET parent = :parent, name = :name WHERE id = :id",
+                    )?;
+                    query.bind((":id", source))?;
+                    query.bind((":parent", parent))?;
+                    query.bind((":name", name))?;
+                    query.next()?;
+
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    /// Merges the category at the `source` path into the existing category at
+    /// the `target` path, a missing target is not created.
+    pub async fn merge_category_paths(
+        &self,
+        owner: i64,
+        source: &str,
+        target: &str,
+    ) -> Result<(), Error> {
+        debug!(
+            "Merge category paths: source = {}, target = {}",
+            source, target
+        );
+
+        let lock = self.inner.lock().await;
+
+        transaction(&lock, |connection| {
+            let source = find_category_path(connection, owner, &split_category_path(source))?;
+            let target = find_category_path(connection, owner, &split_category_path(target))?;
+
+            if is_category_descendant(connection, owner, target, source)? {
+                return Err(Error::Invalid(
+                    "Category can not be merged into itself".into(),
+                ));
+            }
+
+            merge_category_nodes(connection, owner, source, target)
+        })
+    }
+
+    /// Renames every category node named `name` that has products assigned,
+    /// nodes are merged with siblings which already have the new name.
+    pub async fn rename_category_names(
+        &self,
+        owner: i64,
+        name: &str,
+        new_name: &str,
+    ) -> Result<usize, Error> {
+        debug!(
+            "Rename category names: name = {}, new name = {}",
+            name, new_name
+        );
+
+        let lock = self.inner.lock().await;
+
+        transaction(&lock, |connection| {
+            let mut query = connection.prepare(
+                "SELECT id, parent FROM category_nodes
+                WHERE owner = :owner AND name = :name AND id IN (SELECT node FROM products)",
+            )?;
+            query.bind((":owner", owner))?;
+            query.bind((":name", name))?;
+
+            let mut nodes: Vec<(i64, Option<i64>)> = Vec::new();
+
+            while let State::Row = query.next()? {
+                nodes.push((query.read(0)?, query.read(1)?));
+            }
+
+            drop(query);
+
+            for (id, parent) in &nodes {
+                match find_category_node(connection, owner, *parent, new_name)? {
+                    Some(target) => merge_category_nodes(connection, owner, *id, target)?,
+                    None => {
+                        validate_category_name(new_name)?;
+
+                        let mut query = connection
+                            .prepare("UPDATE category_nodes SET name = :name WHERE id = :id")?;
+                        query.bind((":id", *id))?;
+                        query.bind((":name", new_name))?;
+                        query.next()?;
+                    }
+                }
+            }
+
+            Ok(nodes.len())
+        })
     }
 
-    pub async fn ticket_item_count(&self, ticket: &str) -> Result<usize, Box<dyn Error>> {
-        debug!("Count ticket: {}", ticket);
+    pub async fn ticket_item_count(&self, owner: i64, ticket: &str) -> Result<usize, Error> {
+        debug!("Count ticket: owner = {}, ticket = {}", owner, ticket);
 
         let lock = self.inner.lock().await;
-        let mut query = lock.prepare("SELECT COUNT(*) FROM tickets WHERE ticket = :ticket")?;
+        let mut query =
+            lock.prepare("SELECT COUNT(*) FROM tickets WHERE owner = :owner AND ticket = :ticket")?;
+        query.bind((":owner", owner))?;
         query.bind((":ticket", ticket))?;
 
         let result = match query.next()? {
@@ -88,53 +378,128 @@ impl Database {
         Ok(result as usize)
     }
 
-    pub async fn remove_ticket_items(&self) -> Result<(), Box<dyn Error>> {
-        debug!("Remove ticket items");
+    /// Removes all receipts of the household with their items, splits and
+    /// payments.
+    pub async fn remove_ticket_items(&self, owner: i64) -> Result<(), Error> {
+        debug!("Remove ticket items: {}", owner);
 
         let lock = self.inner.lock().await;
-        let mut query = lock.prepare("DELETE FROM tickets")?;
-        query.next()?;
 
-        Ok(())
+        transaction(&lock, |connection| {
+            let mut query = connection.prepare(
+                "DELETE FROM splits WHERE item IN (SELECT id FROM tickets WHERE owner = :owner)",
+            )?;
+            query.bind((":owner", owner))?;
+            query.next()?;
+
+            let mut query = connection.prepare("DELETE FROM payments WHERE owner = :owner")?;
+            query.bind((":owner", owner))?;
+            query.next()?;
+
+            let mut query = connection.prepare("DELETE FROM tickets WHERE owner = :owner")?;
+            query.bind((":owner", owner))?;
+            query.next()?;
+
+            let mut query = connection.prepare("DELETE FROM receipts WHERE owner = :owner")?;
+            query.bind((":owner", owner))?;
+            query.next()?;
+
+            Ok(())
+        })
+    }
+
+    pub async fn remove_receipt(&self, owner: i64, ticket: &str) -> Result<(), Error> {
+        debug!("Remove receipt: owner = {}, ticket = {}", owner, ticket);
+
+        let lock = self.inner.lock().await;
+
+        transaction(&lock, |connection| {
+            let mut query = connection.prepare(
+                "DELETE FROM splits
+                WHERE item IN (SELECT id FROM tickets WHERE owner = :owner AND ticket = :ticket)",
+            )?;
+            query.bind((":owner", owner))?;
+            query.bind((":ticket", ticket))?;
+            query.next()?;
+
+            let mut query = connection
+                .prepare("DELETE FROM payments WHERE owner = :owner AND ticket = :ticket")?;
+            query.bind((":owner", owner))?;
+            query.bind((":ticket", ticket))?;
+            query.next()?;
+
+            let mut query = connection
+                .prepare("DELETE FROM tickets WHERE owner = :owner AND ticket = :ticket")?;
+            query.bind((":owner", owner))?;
+            query.bind((":ticket", ticket))?;
+            query.next()?;
+
+            if connection.change_count() == 0 {
+                return Err(Error::NotFound(format!("Receipt {} not found", ticket)));
+            }
+
+            let mut query = connection
+                .prepare("DELETE FROM receipts WHERE owner = :owner AND ticket = :ticket")?;
+            query.bind((":owner", owner))?;
+            query.bind((":ticket", ticket))?;
+            query.next()?;
+
+            Ok(())
+        })
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn insert_ticket_item(
         &self,
+        owner: i64,
         ticket: &str,
         date: &str,
         product: &str,
         quantity: f64,
         sum: f64,
-    ) -> Result<(), Box<dyn Error>> {
+        price: Option<f64>,
+        unit: &str,
+        unit_quantity: f64,
+    ) -> Result<(), Error> {
         debug!(
-            "Insert ticket: ticket = {}, date = {}, product = {}, quantity = {}, sum = {}",
-            ticket, date, product, quantity, sum
+            "Insert ticket: owner = {}, ticket = {}, date = {}, product = {}, quantity = {}, sum = {}, price = {:?}, unit = {}, unit quantity = {}",
+            owner, ticket, date, product, quantity, sum, price, unit, unit_quantity
         );
 
         let lock = self.inner.lock().await;
         let mut query = lock.prepare(
-            "INSERT INTO tickets (ticket, date, product, quantity, sum) VALUES (:ticket, :date, :product, :quantity, :sum)",
+            "INSERT INTO tickets (owner, ticket, date, product, quantity, sum, price, unit, unit_quantity)
+            VALUES (:owner, :ticket, :date, :product, :quantity, :sum, :price, :unit, :unit_quantity)",
         )?;
+        query.bind((":owner", owner))?;
         query.bind((":ticket", ticket))?;
         query.bind((":date", date))?;
         query.bind((":product", product))?;
         query.bind((":quantity", quantity))?;
         query.bind((":sum", sum))?;
+        query.bind((":price", price))?;
+        query.bind((":unit", unit))?;
+        query.bind((":unit_quantity", unit_quantity))?;
         query.next()?;
 
         Ok(())
     }
 
-    pub async fn select_ticket_items(&self) -> Result<Vec<TicketItemData>, Box<dyn Error>> {
-        debug!("Ticket items");
+    pub async fn select_ticket_items(&self, owner: i64) -> Result<Vec<TicketItemData>, Error> {
+        debug!("Ticket items: {}", owner);
 
         let lock = self.inner.lock().await;
         let mut query = lock.prepare(
-            "SELECT t.date, t.product, p.category, p.name, t.quantity, t.sum
+            "SELECT t.date, t.product, p.category, p.name, t.quantity, t.sum, t.unit, t.unit_quantity, t.ticket, u.login
         	FROM tickets AS t
-        		LEFT OUTER JOIN products AS p ON (p.product = t.product)
+        		LEFT OUTER JOIN product_categories AS p ON (p.owner = t.owner AND p.product = t.product)
+        		LEFT OUTER JOIN receipts AS r ON (r.owner = t.owner AND r.ticket = t.ticket)
+        		LEFT OUTER JOIN users AS u ON (u.id = r.user)
+        	WHERE t.owner = :owner
         	ORDER BY t.date, t.product",
         )?;
+        query.bind((":owner", owner))?;
+
         let mut result = Vec::new();
 
         while let State::Row = query.next()? {
@@ -144,10 +509,1752 @@ impl Database {
             let name = query.read(3)?;
             let quantity = query.read(4)?;
             let sum = query.read(5)?;
-            let item = TicketItemData::new(date, product, category, name, quantity, sum);
+            let unit = query.read(6)?;
+            let unit_quantity = query.read(7)?;
+            let ticket = query.read(8)?;
+            let member = query.read(9)?;
+            let item = TicketItemData::new(date, product, category, name, quantity, sum)
+                .with_unit(unit, unit_quantity)
+                .with_receipt(ticket, member);
+            result.push(item);
+        }
+
+        Ok(result)
+    }
+
+    /// Selects the header and the items of one receipt. Receipts saved before
+    /// headers were stored have only items.
+    pub async fn select_receipt(&self, owner: i64, ticket: &str) -> Result<ReceiptData, Error> {
+        debug!("Receipt: owner = {}, ticket = {}", owner, ticket);
+
+        let lock = self.inner.lock().await;
+        let mut query = lock.prepare(
+            "SELECT r.time, r.inn, r.seller, r.address, r.total, r.cash, r.card,
+                r.fiscal_drive, r.fiscal_document, r.fiscal_sign, r.register, r.scanned, u.login
+            FROM receipts AS r LEFT OUTER JOIN users AS u ON (u.id = r.user)
+            WHERE r.owner = :owner AND r.ticket = :ticket",
+        )?;
+        query.bind((":owner", owner))?;
+        query.bind((":ticket", ticket))?;
+
+        let mut receipt = match query.next()? {
+            State::Row => ReceiptData::new(ticket.into(), query.read(0)?)
+                .with_store(query.read(1)?, query.read(2)?, query.read(3)?)
+                .with_payment(query.read(4)?, query.read(5)?, query.read(6)?)
+                .with_fiscal(
+                    query.read(7)?,
+                    query.read(8)?,
+                    query.read(9)?,
+                    query.read(10)?,
+                )
+                .with_scan(query.read(11)?, query.read(12)?),
+            State::Done => ReceiptData::new(ticket.into(), None),
+        };
+        drop(query);
+
+        let mut query = lock.prepare(
+            "SELECT t.date, t.product, p.category, p.name, t.quantity, t.sum, t.unit, t.unit_quantity
+            FROM tickets AS t
+                LEFT OUTER JOIN product_categories AS p ON (p.owner = t.owner AND p.product = t.product)
+            WHERE t.owner = :owner AND t.ticket = :ticket
+            ORDER BY t.product",
+        )?;
+        query.bind((":owner", owner))?;
+        query.bind((":ticket", ticket))?;
+
+        while let State::Row = query.next()? {
+            let date = query.read(0)?;
+            let product = query.read(1)?;
+            let category = query.read(2)?;
+            let name = query.read(3)?;
+            let quantity = query.read(4)?;
+            let sum = query.read(5)?;
+            let unit = query.read(6)?;
+            let unit_quantity = query.read(7)?;
+            let item = TicketItemData::new(date, product, category, name, quantity, sum)
+                .with_unit(unit, unit_quantity)
+                .with_receipt(ticket.into(), receipt.member().map(String::from));
+            receipt.push_item(item);
+        }
+
+        if receipt.items().is_empty() {
+            return Err(Error::NotFound(format!("Receipt {} not found", ticket)));
+        }
+
+        Ok(receipt)
+    }
+
+    pub async fn select_unmeasured_ticket_items(&self) -> Result<Vec<(i64, String, f64)>, Error> {
+        debug!("Ticket items without unit");
+
+        let lock = self.inner.lock().await;
+        let mut query =
+            lock.prepare("SELECT id, product, quantity FROM tickets WHERE unit IS NULL")?;
+        let mut result = Vec::new();
+
+        while let State::Row = query.next()? {
+            let id = query.read(0)?;
+            let product = query.read(1)?;
+            let quantity = query.read(2)?;
+
+            result.push((id, product, quantity));
+        }
+
+        Ok(result)
+    }
+
+    pub async fn update_ticket_item_unit(
+        &self,
+        id: i64,
+        unit: &str,
+        unit_quantity: f64,
+    ) -> Result<(), Error> {
+        debug!(
+            "Update ticket item unit: id = {}, unit = {}, unit quantity = {}",
+            id, unit, unit_quantity
+        );
+
+        let lock = self.inner.lock().await;
+        let mut query = lock.prepare(
+            "UPDATE tickets SET unit = :unit, unit_quantity = :unit_quantity WHERE id = :id",
+        )?;
+        query.bind((":id", id))?;
+        query.bind((":unit", unit))?;
+        query.bind((":unit_quantity", unit_quantity))?;
+        query.next()?;
+
+        Ok(())
+    }
+
+    pub async fn select_product_category(
+        &self,
+        owner: i64,
+        product: &str,
+    ) -> Result<Option<ProductData>, Error> {
+        debug!("Product category: owner = {}, product = {}", owner, product);
+
+        let lock = self.inner.lock().await;
+        let mut query = lock.prepare(
+            "SELECT p.product, c.title, p.category, p.name
+            FROM product_categories AS p
+                LEFT OUTER JOIN product_canonicals AS pc ON (pc.product = p.product)
+                LEFT OUTER JOIN canonical_products AS c ON (c.id = pc.canonical)
+            WHERE p.owner = :owner AND p.product = :product",
+        )?;
+        query.bind((":owner", owner))?;
+        query.bind((":product", product))?;
+
+        let result = match query.next()? {
+            State::Row => {
+                let product = query.read(0)?;
+                let canonical = query.read(1)?;
+                let category = query.read(2)?;
+                let name = query.read(3)?;
+
+                Some(ProductData::new(product, canonical, category, name))
+            }
+            State::Done => None,
+        };
+
+        Ok(result)
+    }
+
+    pub async fn select_product_inns(&self, owner: i64) -> Result<Vec<(String, String)>, Error> {
+        debug!("Product INNs: {}", owner);
+
+        let lock = self.inner.lock().await;
+        let mut query = lock.prepare(
+            "SELECT DISTINCT t.product, r.inn
+            FROM tickets AS t INNER JOIN receipts AS r ON (r.owner = t.owner AND r.ticket = t.ticket)
+            WHERE t.owner = :owner AND r.inn IS NOT NULL",
+        )?;
+        query.bind((":owner", owner))?;
+
+        let mut result = Vec::new();
+
+        while let State::Row = query.next()? {
+            let product = query.read(0)?;
+            let inn = query.read(1)?;
+
+            result.push((product, inn));
+        }
+
+        Ok(result)
+    }
+
+    pub async fn insert_receipt(
+        &self,
+        owner: i64,
+        user: Option<i64>,
+        receipt: &ReceiptData,
+    ) -> Result<(), Error> {
+        debug!(
+            "Insert receipt: owner = {}, user = {:?}, receipt = {:?}",
+            owner, user, receipt
+        );
+
+        let lock = self.inner.lock().await;
+        let mut query = lock.prepare(
+            "INSERT OR REPLACE INTO receipts (owner, user, ticket, time, inn, seller, address, total, cash, card,
+                fiscal_drive, fiscal_document, fiscal_sign, register, scanned)
+            VALUES (:owner, :user, :ticket, :time, :inn, :seller, :address, :total, :cash, :card,
+                :fiscal_drive, :fiscal_document, :fiscal_sign, :register, :scanned)",
+        )?;
+        query.bind((":owner", owner))?;
+        query.bind((":user", user))?;
+        query.bind((":ticket", receipt.ticket()))?;
+        query.bind((":time", receipt.time()))?;
+        query.bind((":inn", receipt.inn()))?;
+        query.bind((":seller", receipt.seller()))?;
+        query.bind((":address", receipt.address()))?;
+        query.bind((":total", receipt.total()))?;
+        query.bind((":cash", receipt.cash()))?;
+        query.bind((":card", receipt.card()))?;
+        query.bind((":fiscal_drive", receipt.fiscal_drive()))?;
+        query.bind((":fiscal_document", receipt.fiscal_document()))?;
+        query.bind((":fiscal_sign", receipt.fiscal_sign()))?;
+        query.bind((":register", receipt.register()))?;
+        query.bind((":scanned", receipt.scanned()))?;
+        query.next()?;
+
+        Ok(())
+    }
+
+    pub async fn select_rules(&self, owner: i64) -> Result<Vec<RuleData>, Error> {
+        debug!("Rules: {}", owner);
+
+        let lock = self.inner.lock().await;
+        let mut query = lock.prepare(
+            "SELECT id, priority, kind, pattern, inn, category, name
+            FROM rules
+            WHERE owner = :owner
+            ORDER BY priority DESC, id",
+        )?;
+        query.bind((":owner", owner))?;
+
+        let mut result = Vec::new();
+
+        while let State::Row = query.next()? {
+            let id = query.read(0)?;
+            let priority = query.read(1)?;
+            let kind = query.read(2)?;
+            let pattern = query.read(3)?;
+            let inn = query.read(4)?;
+            let category = query.read(5)?;
+            let name = query.read(6)?;
+            let item = RuleData::new(id, priority, kind, pattern, inn, category, name);
+
             result.push(item);
         }
 
         Ok(result)
     }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn update_rule(
+        &self,
+        owner: i64,
+        id: Option<i64>,
+        priority: i64,
+        kind: &str,
+        pattern: &str,
+        inn: Option<&str>,
+        category: &str,
+        name: &str,
+    ) -> Result<(), Error> {
+        debug!(
+            "Update rule: owner = {}, id = {:?}, priority = {}, kind = {}, pattern = {}, inn = {:?}, category = {}, name = {}",
+            owner, id, priority, kind, pattern, inn, category, name
+        );
+
+        let lock = self.inner.lock().await;
+        let mut query = match id {
+            Some(id) => {
+                let mut query = lock.prepare(
+                    "UPDATE rules
+                    SET priority = :priority, kind = :kind, pattern = :pattern, inn = :inn, category = :category, name = :name
+                    WHERE id = :id AND owner = :owner",
+                )?;
+                query.bind((":id", id))?;
+                query
+            }
+            None => lock.prepare(
+                "INSERT INTO rules (owner, priority, kind, pattern, inn, category, name)
+                VALUES (:owner, :priority, :kind, :pattern, :inn, :category, :name)",
+            )?,
+        };
+        query.bind((":owner", owner))?;
+        query.bind((":priority", priority))?;
+        query.bind((":kind", kind))?;
+        query.bind((":pattern", pattern))?;
+        query.bind((":inn", inn))?;
+        query.bind((":category", category))?;
+        query.bind((":name", name))?;
+        query.next()?;
+
+        if lock.change_count() == 0 {
+            return Err(Error::NotFound(format!(
+                "Rule {} not found",
+                id.unwrap_or_default()
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub async fn remove_rule(&self, owner: i64, id: i64) -> Result<(), Error> {
+        debug!("Remove rule: owner = {}, id = {}", owner, id);
+
+        let lock = self.inner.lock().await;
+        let mut query = lock.prepare("DELETE FROM rules WHERE id = :id AND owner = :owner")?;
+        query.bind((":id", id))?;
+        query.bind((":owner", owner))?;
+        query.next()?;
+
+        if lock.change_count() == 0 {
+            return Err(Error::NotFound(format!("Rule {} not found", id)));
+        }
+
+        Ok(())
+    }
+
+    pub async fn select_uncanonical_products(&self) -> Result<Vec<String>, Error> {
+        debug!("Products without canonical product");
+
+        let lock = self.inner.lock().await;
+        let mut query = lock.prepare(
+            "SELECT DISTINCT product FROM tickets
+            WHERE product NOT IN (SELECT product FROM product_canonicals)",
+        )?;
+        let mut result = Vec::new();
+
+        while let State::Row = query.next()? {
+            result.push(query.read(0)?);
+        }
+
+        Ok(result)
+    }
+
+    pub async fn insert_product_canonical(
+        &self,
+        product: &str,
+        key: &str,
+        title: &str,
+        amount: Option<f64>,
+        unit: Option<&str>,
+        fat: Option<f64>,
+    ) -> Result<i64, Error> {
+        debug!(
+            "Insert product canonical: product = {}, key = {}, title = {}",
+            product, key, title
+        );
+
+        let lock = self.inner.lock().await;
+
+        transaction(&lock, |connection| {
+            let mut query = connection.prepare(
+                "INSERT OR IGNORE INTO canonical_products (key, title, amount, unit, fat)
+                VALUES (:key, :title, :amount, :unit, :fat)",
+            )?;
+            query.bind((":key", key))?;
+            query.bind((":title", title))?;
+            query.bind((":amount", amount))?;
+            query.bind((":unit", unit))?;
+            query.bind((":fat", fat))?;
+            query.next()?;
+
+            let mut query =
+                connection.prepare("SELECT id FROM canonical_products WHERE key = :key")?;
+            query.bind((":key", key))?;
+            query.next()?;
+
+            let canonical: i64 = query.read(0)?;
+            let mut query = connection.prepare(
+                "INSERT OR REPLACE INTO product_canonicals (product, canonical) VALUES (:product, :canonical)",
+            )?;
+            query.bind((":product", product))?;
+            query.bind((":canonical", canonical))?;
+            query.next()?;
+
+            Ok(canonical)
+        })
+    }
+
+    /// Assigns uncategorized products the category most used by other
+    /// products of the same canonical product of the same owner.
+    pub async fn inherit_canonical_categories(&self, owner: i64) -> Result<usize, Error> {
+        debug!("Inherit canonical categories: {}", owner);
+
+        let lock = self.inner.lock().await;
+        let mut query = lock.prepare(
+            "INSERT INTO products (owner, product, node)
+            SELECT owner, product, node FROM (
+                SELECT DISTINCT t.owner, t.product, (
+                    SELECT p.node
+                    FROM product_canonicals AS s
+                        INNER JOIN products AS p ON (p.owner = t.owner AND p.product = s.product)
+                    WHERE s.canonical = c.canonical
+                    GROUP BY p.node
+                    ORDER BY COUNT(*) DESC
+                    LIMIT 1
+                ) AS node
+                FROM tickets AS t INNER JOIN product_canonicals AS c ON (c.product = t.product)
+                WHERE t.owner = :owner AND NOT EXISTS (
+                    SELECT 1 FROM products AS p WHERE p.owner = t.owner AND p.product = t.product
+                )
+            )
+            WHERE node IS NOT NULL",
+        )?;
+        query.bind((":owner", owner))?;
+        query.next()?;
+
+        Ok(lock.change_count())
+    }
+
+    pub async fn select_canonical_products(
+        &self,
+        owner: i64,
+    ) -> Result<Vec<CanonicalProductData>, Error> {
+        debug!("Canonical products: {}", owner);
+
+        let lock = self.inner.lock().await;
+        let mut query = lock.prepare(
+            "SELECT c.id, c.title, c.amount, c.unit, c.fat, pc.product, p.category, p.name
+            FROM canonical_products AS c
+                INNER JOIN product_canonicals AS pc ON (pc.canonical = c.id)
+                LEFT OUTER JOIN product_categories AS p ON (p.owner = :owner AND p.product = pc.product)
+            WHERE pc.product IN (SELECT product FROM tickets WHERE owner = :owner)
+            ORDER BY c.title, c.id, pc.product",
+        )?;
+        query.bind((":owner", owner))?;
+
+        let mut result: Vec<CanonicalProductData> = Vec::new();
+
+        while let State::Row = query.next()? {
+            let id = query.read(0)?;
+
+            if result.last().map(|item| item.id()) != Some(id) {
+                let title = query.read(1)?;
+                let amount = query.read(2)?;
+                let unit = query.read(3)?;
+                let fat = query.read(4)?;
+
+                result.push(CanonicalProductData::new(id, title, amount, unit, fat));
+            }
+
+            let product = query.read(5)?;
+            let canonical = query.read(1)?;
+            let category = query.read(6)?;
+            let name = query.read(7)?;
+            let item = ProductData::new(product, canonical, category, name);
+
+            if let Some(last) = result.last_mut() {
+                last.push_product(item);
+            }
+        }
+
+        Ok(result)
+    }
+
+    pub async fn select_category_nodes(&self, owner: i64) -> Result<Vec<CategoryNodeData>, Error> {
+        debug!("Category nodes: {}", owner);
+
+        let lock = self.inner.lock().await;
+        let mut query = lock.prepare(
+            "SELECT n.id, n.parent, n.name, IFNULL(s.sum, 0.0)
+            FROM category_nodes AS n
+                LEFT OUTER JOIN (
+                    SELECT p.node, SUM(t.sum) AS sum
+                    FROM tickets AS t INNER JOIN products AS p ON (p.owner = t.owner AND p.product = t.product)
+                    WHERE t.owner = :owner
+                    GROUP BY p.node
+                ) AS s ON (s.node = n.id)
+            WHERE n.owner = :owner
+            ORDER BY n.name",
+        )?;
+        query.bind((":owner", owner))?;
+
+        let mut result = Vec::new();
+
+        while let State::Row = query.next()? {
+            let id = query.read(0)?;
+            let parent = query.read(1)?;
+            let name = query.read(2)?;
+            let sum = query.read(3)?;
+            let item = CategoryNodeData::new(id, parent, name, sum);
+
+            result.push(item);
+        }
+
+        Ok(result)
+    }
+
+    pub async fn insert_category_node(
+        &self,
+        owner: i64,
+        parent: Option<i64>,
+        name: &str,
+    ) -> Result<i64, Error> {
+        debug!(
+            "Insert category node: parent = {:?}, name = {}",
+            parent, name
+        );
+
+        let lock = self.inner.lock().await;
+
+        transaction(&lock, |connection| {
+            if let Some(parent) = parent {
+                select_category_name(connection, owner, parent)?;
+            }
+
+            if find_category_node(connection, owner, parent, name)?.is_some() {
+                return Err(Error::Duplicate(format!(
+                    "Category {} already exists",
+                    name
+                )));
+            }
+
+            insert_category_node(connection, owner, parent, name)
+        })
+    }
+
+    pub async fn rename_category_node(&self, owner: i64, id: i64, name: &str) -> Result<(), Error> {
+        debug!("Rename category node: id = {}, name = {}", id, name);
+
+        let lock = self.inner.lock().await;
+
+        transaction(&lock, |connection| {
+            let parent = select_category_parent(connection, owner, id)?;
+
+            match find_category_node(connection, owner, parent, name)? {
+                Some(existing) if existing != id => Err(Error::Duplicate(format!(
+                    "Category {} already exists",
+                    name
+                ))),
+                _ => {
+                    validate_category_name(name)?;
+
+                    let mut query = connection
+                        .prepare("UPDATE category_nodes SET name = :name WHERE id = :id")?;
+                    query.bind((":id", id))?;
+                    query.bind((":name", name))?;
+                    query.next()?;
+
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    pub async fn move_category_node(
+        &self,
+        owner: i64,
+        id: i64,
+        parent: Option<i64>,
+    ) -> Result<(), Error> {
+        debug!("Move category node: id = {}, parent = {:?}", id, parent);
+
+        let lock = self.inner.lock().await;
+
+        transaction(&lock, |connection| {
+            if let Some(parent) = parent {
+                if is_category_descendant(connection, owner, parent, id)? {
+                    return Err(Error::Invalid(
+                        "Category can not be moved into itself".into(),
+                    ));
+                }
+            }
+
+            let name = select_category_name(connection, owner, id)?;
+
+            if find_category_node(connection, owner, parent, &name)?.is_some() {
+                return Err(Error::Duplicate(format!(
+                    "Category {} already exists",
+                    name
+                )));
+            }
+
+            let mut query =
+                connection.prepare("UPDATE category_nodes SET parent = :parent WHERE id = :id")?;
+            query.bind((":id", id))?;
+            query.bind((":parent", parent))?;
+            query.next()?;
+
+            Ok(())
+        })
+    }
+
+    pub async fn merge_category_nodes(
+        &self,
+        owner: i64,
+        source: i64,
+        target: i64,
+    ) -> Result<(), Error> {
+        debug!(
+            "Merge category nodes: source = {}, target = {}",
+            source, target
+        );
+
+        let lock = self.inner.lock().await;
+
+        transaction(&lock, |connection| {
+            select_category_name(connection, owner, source)?;
+
+            if is_category_descendant(connection, owner, target, source)? {
+                return Err(Error::Invalid(
+                    "Category can not be merged into itself".into(),
+                ));
+            }
+
+            merge_category_nodes(connection, owner, source, target)
+        })
+    }
+
+    pub async fn select_canonical_prices(
+        &self,
+        owner: i64,
+        canonical: i64,
+    ) -> Result<Vec<PriceData>, Error> {
+        debug!(
+            "Canonical product prices: owner = {}, canonical = {}",
+            owner, canonical
+        );
+
+        let lock = self.inner.lock().await;
+        let mut query = lock.prepare(
+            "SELECT t.date, t.product, r.seller, r.inn, r.address, t.unit, t.unit_quantity, t.sum
+            FROM tickets AS t
+                INNER JOIN product_canonicals AS pc ON (pc.product = t.product)
+                LEFT OUTER JOIN receipts AS r ON (r.owner = t.owner AND r.ticket = t.ticket)
+            WHERE t.owner = :owner AND pc.canonical = :canonical
+            ORDER BY t.date",
+        )?;
+        query.bind((":owner", owner))?;
+        query.bind((":canonical", canonical))?;
+
+        let mut result = Vec::new();
+
+        while let State::Row = query.next()? {
+            let date = query.read(0)?;
+            let product = query.read(1)?;
+            let seller = query.read(2)?;
+            let inn = query.read(3)?;
+            let address = query.read(4)?;
+            let unit = query.read(5)?;
+            let unit_quantity = query.read(6)?;
+            let sum = query.read(7)?;
+            let item = PriceData::new(
+                date,
+                product,
+                seller,
+                inn,
+                address,
+                unit,
+                unit_quantity,
+                sum,
+            );
+
+            result.push(item);
+        }
+
+        Ok(result)
+    }
+
+    pub async fn select_monthly_prices(&self, owner: i64) -> Result<Vec<MonthlyPriceData>, Error> {
+        debug!("Monthly prices: {}", owner);
+
+        let lock = self.inner.lock().await;
+        let mut query = lock.prepare(
+            "SELECT pc.canonical, IFNULL(t.unit, ''), substr(t.date, 1, 7) AS month, SUM(t.sum), SUM(t.unit_quantity),
+                COUNT(*)
+            FROM tickets AS t INNER JOIN product_canonicals AS pc ON (pc.product = t.product)
+            WHERE t.owner = :owner AND t.unit_quantity > 0
+            GROUP BY pc.canonical, t.unit, month
+            ORDER BY month",
+        )?;
+        query.bind((":owner", owner))?;
+
+        let mut result = Vec::new();
+
+        while let State::Row = query.next()? {
+            let canonical = query.read(0)?;
+            let unit = query.read(1)?;
+            let month = query.read(2)?;
+            let sum = query.read(3)?;
+            let unit_quantity = query.read(4)?;
+            let purchases = query.read::<i64, _>(5)? as usize;
+            let item = MonthlyPriceData::new(canonical, unit, month, sum, unit_quantity, purchases);
+
+            result.push(item);
+        }
+
+        Ok(result)
+    }
+
+    /// Selects purchases with known unit quantity and store, the store is
+    /// identified by the seller INN together with the address, so stores of
+    /// one chain are told apart.
+    pub async fn select_store_prices(&self, owner: i64) -> Result<Vec<StorePriceData>, Error> {
+        debug!("Store prices: {}", owner);
+
+        let lock = self.inner.lock().await;
+        let mut query = lock.prepare(
+            "SELECT pc.canonical, c.title, r.inn, IFNULL(r.address, r.inn) AS store, r.seller, t.date, t.unit,
+                t.unit_quantity, t.sum
+            FROM tickets AS t
+                INNER JOIN product_canonicals AS pc ON (pc.product = t.product)
+                INNER JOIN canonical_products AS c ON (c.id = pc.canonical)
+                INNER JOIN receipts AS r ON (r.owner = t.owner AND r.ticket = t.ticket)
+            WHERE t.owner = :owner AND t.unit_quantity > 0 AND store IS NOT NULL
+            ORDER BY pc.canonical, t.unit, r.inn, store, t.date",
+        )?;
+        query.bind((":owner", owner))?;
+
+        let mut result = Vec::new();
+
+        while let State::Row = query.next()? {
+            let canonical = query.read(0)?;
+            let title = query.read(1)?;
+            let inn = query.read(2)?;
+            let store = query.read(3)?;
+            let seller = query.read(4)?;
+            let date = query.read(5)?;
+            let unit = query.read(6)?;
+            let unit_quantity = query.read(7)?;
+            let sum = query.read(8)?;
+            let item = StorePriceData::new(
+                canonical,
+                title,
+                inn,
+                store,
+                seller,
+                date,
+                unit,
+                unit_quantity,
+                sum,
+            );
+
+            result.push(item);
+        }
+
+        Ok(result)
+    }
+
+    /// Selects budgets with amounts spent in the month, spending of a category
+    /// includes all its subcategories. Month has `YYYY.MM` format.
+    pub async fn select_budgets(&self, owner: i64, month: &str) -> Result<Vec<BudgetData>, Error> {
+        debug!("Budgets: owner = {}, month = {}", owner, month);
+
+        let lock = self.inner.lock().await;
+        let mut query = lock.prepare(
+            "WITH RECURSIVE subtree (root, id) AS (
+                SELECT b.node, b.node
+                FROM budgets AS b INNER JOIN category_nodes AS n ON (n.id = b.node)
+                WHERE n.owner = :owner
+                UNION ALL
+                SELECT s.root, n.id FROM category_nodes AS n INNER JOIN subtree AS s ON (n.parent = s.id)
+            ), spent (node, sum) AS (
+                SELECT s.root, SUM(t.sum)
+                FROM subtree AS s
+                    INNER JOIN products AS p ON (p.node = s.id)
+                    INNER JOIN tickets AS t ON (t.owner = p.owner AND t.product = p.product)
+                WHERE substr(t.date, 1, 7) = :month
+                GROUP BY s.root
+            )
+            SELECT b.node, CASE WHEN c.category = '' THEN c.name ELSE c.category || ' / ' || c.name END AS path, b.amount, IFNULL(s.sum, 0.0)
+            FROM budgets AS b
+                INNER JOIN category_paths AS c ON (c.id = b.node)
+                INNER JOIN category_nodes AS n ON (n.id = b.node)
+                LEFT OUTER JOIN spent AS s ON (s.node = b.node)
+            WHERE n.owner = :owner
+            ORDER BY path",
+        )?;
+        query.bind((":owner", owner))?;
+        query.bind((":month", month))?;
+
+        let mut result = Vec::new();
+
+        while let State::Row = query.next()? {
+            let node = query.read(0)?;
+            let category = query.read(1)?;
+            let amount = query.read(2)?;
+            let spent = query.read(3)?;
+            let item = BudgetData::new(node, category, amount, spent);
+
+            result.push(item);
+        }
+
+        Ok(result)
+    }
+
+    /// Sets the monthly budget of an existing category.
+    pub async fn update_budget(
+        &self,
+        owner: i64,
+        category: &str,
+        amount: f64,
+    ) -> Result<(), Error> {
+        debug!(
+            "Update budget: owner = {}, category = {}, amount = {}",
+            owner, category, amount
+        );
+
+        if !amount.is_finite() || amount < 0.0 {
+            return Err(Error::Invalid(format!("Invalid budget amount: {}", amount)));
+        }
+
+        let lock = self.inner.lock().await;
+
+        transaction(&lock, |connection| {
+            let node = find_category_path(connection, owner, &split_category_path(category))?;
+            let mut query = connection
+                .prepare("INSERT OR REPLACE INTO budgets (node, amount) VALUES (:node, :amount)")?;
+            query.bind((":node", node))?;
+            query.bind((":amount", amount))?;
+            query.next()?;
+
+            Ok(())
+        })
+    }
+
+    pub async fn remove_budget(&self, owner: i64, node: i64) -> Result<(), Error> {
+        debug!("Remove budget: owner = {}, node = {}", owner, node);
+
+        let lock = self.inner.lock().await;
+        let mut query = lock.prepare(
+            "DELETE FROM budgets
+            WHERE node = :node AND node IN (SELECT id FROM category_nodes WHERE owner = :owner)",
+        )?;
+        query.bind((":node", node))?;
+        query.bind((":owner", owner))?;
+        query.next()?;
+
+        Ok(())
+    }
+
+    /// Creates a user together with a new household administrated by the
+    /// user.
+    pub async fn insert_user(&self, login: &str, password: &str) -> Result<UserData, Error> {
+        debug!("Insert user: {}", login);
+
+        let lock = self.inner.lock().await;
+
+        transaction(&lock, |connection| {
+            let mut query = connection.prepare("INSERT INTO households (name) VALUES (:name)")?;
+            query.bind((":name", login))?;
+            query.next()?;
+
+            let household = last_insert_id(connection)?;
+
+            insert_user(connection, login, password, household, ROLE_ADMIN)
+        })
+    }
+
+    pub async fn insert_member(
+        &self,
+        household: i64,
+        login: &str,
+        password: &str,
+        role: &str,
+    ) -> Result<UserData, Error> {
+        debug!(
+            "Insert member: household = {}, login = {}, role = {}",
+            household, login, role
+        );
+
+        let lock = self.inner.lock().await;
+
+        transaction(&lock, |connection| {
+            insert_user(connection, login, password, household, role)
+        })
+    }
+
+    pub async fn select_members(&self, household: i64) -> Result<Vec<UserData>, Error> {
+        debug!("Members: {}", household);
+
+        let lock = self.inner.lock().await;
+        let mut query = lock.prepare(
+            "SELECT id, login, household, role FROM users WHERE household = :household ORDER BY login",
+        )?;
+        query.bind((":household", household))?;
+
+        let mut result = Vec::new();
+
+        while let State::Row = query.next()? {
+            let id = query.read(0)?;
+            let login = query.read(1)?;
+            let household = query.read(2)?;
+            let role = query.read(3)?;
+
+            result.push(UserData::new(id, login, household, role));
+        }
+
+        Ok(result)
+    }
+
+    pub async fn update_member_role(
+        &self,
+        household: i64,
+        user: i64,
+        role: &str,
+    ) -> Result<(), Error> {
+        debug!(
+            "Update member role: household = {}, user = {}, role = {}",
+            household, user, role
+        );
+
+        let lock = self.inner.lock().await;
+
+        transaction(&lock, |connection| {
+            let mut query = connection.prepare(
+                "UPDATE users SET role = :role WHERE id = :id AND household = :household",
+            )?;
+            query.bind((":id", user))?;
+            query.bind((":household", household))?;
+            query.bind((":role", role))?;
+            query.next()?;
+
+            if connection.change_count() == 0 {
+                return Err(Error::NotFound(format!("Member {} not found", user)));
+            }
+
+            check_household_admin(connection, household)
+        })
+    }
+
+    /// Removes the member account together with its sessions and tokens, receipts
+    /// scanned by the member stay in the household.
+    pub async fn remove_member(&self, household: i64, user: i64) -> Result<(), Error> {
+        debug!("Remove member: household = {}, user = {}", household, user);
+
+        let lock = self.inner.lock().await;
+
+        transaction(&lock, |connection| {
+            let mut query = connection
+                .prepare("DELETE FROM users WHERE id = :id AND household = :household")?;
+            query.bind((":id", user))?;
+            query.bind((":household", household))?;
+            query.next()?;
+
+            if connection.change_count() == 0 {
+                return Err(Error::NotFound(format!("Member {} not found", user)));
+            }
+
+            let mut query = connection.prepare("DELETE FROM sessions WHERE user = :user")?;
+            query.bind((":user", user))?;
+            query.next()?;
+
+            let mut query = connection.prepare("DELETE FROM tokens WHERE user = :user")?;
+            query.bind((":user", user))?;
+            query.next()?;
+
+            check_household_admin(connection, household)
+        })
+    }
+
+    /// Fails with `NotFound` when the household does not exist.
+    pub async fn check_household(&self, household: i64) -> Result<(), Error> {
+        debug!("Check household: {}", household);
+
+        let lock = self.inner.lock().await;
+
+        check_household(&lock, household)
+    }
+
+    /// Moves data stored before accounts were introduced to the household.
+    /// The household must not have receipts, categories or rules yet, so
+    /// unique names do not collide. Returns the number of moved receipt items.
+    pub async fn assign_unowned_data(&self, household: i64) -> Result<usize, Error> {
+        debug!("Assign unowned data: {}", household);
+
+        let lock = self.inner.lock().await;
+
+        transaction(&lock, |connection| {
+            check_household(connection, household)?;
+
+            for table in OWNED_TABLES {
+                let mut query = connection.prepare(format!(
+                    "SELECT COUNT(*) FROM {} WHERE owner = :owner",
+                    table
+                ))?;
+                query.bind((":owner", household))?;
+                query.next()?;
+
+                if query.read::<i64, _>(0)? > 0 {
+                    return Err(Error::Invalid(format!(
+                        "Household {} already has data, unowned data is assigned to new households only",
+                        household
+                    )));
+                }
+            }
+
+            let mut count = 0;
+
+            for table in OWNED_TABLES {
+                let mut query = connection.prepare(format!(
+                    "UPDATE {} SET owner = :household WHERE owner = :owner",
+                    table
+                ))?;
+                query.bind((":household", household))?;
+                query.bind((":owner", UNASSIGNED_OWNER))?;
+                query.next()?;
+
+                if *table == "tickets" {
+                    count = connection.change_count();
+                }
+            }
+
+            Ok(count)
+        })
+    }
+
+    /// Selects the user with the password hash to verify at login.
+    pub async fn select_user_password(
+        &self,
+        login: &str,
+    ) -> Result<Option<(UserData, String)>, Error> {
+        debug!("User password: {}", login);
+
+        let lock = self.inner.lock().await;
+
+        select_user_password(&lock, login)
+    }
+
+    pub async fn insert_session(&self, token: &str, user: i64, expires: i64) -> Result<(), Error> {
+        debug!("Insert session: user = {}, expires = {}", user, expires);
+
+        let lock = self.inner.lock().await;
+        let mut query = lock.prepare(
+            "INSERT INTO sessions (token, user, expires) VALUES (:token, :user, :expires)",
+        )?;
+        query.bind((":token", token))?;
+        query.bind((":user", user))?;
+        query.bind((":expires", expires))?;
+        query.next()?;
+
+        Ok(())
+    }
+
+    /// Selects the user of a session which has not expired at `now`.
+    pub async fn select_session_user(
+        &self,
+        token: &str,
+        now: i64,
+    ) -> Result<Option<UserData>, Error> {
+        debug!("Session user");
+
+        let lock = self.inner.lock().await;
+        let mut query = lock.prepare(
+            "SELECT u.id, u.login, u.household, u.role
+            FROM sessions AS s INNER JOIN users AS u ON (u.id = s.user)
+            WHERE s.token = :token AND s.expires > :now",
+        )?;
+        query.bind((":token", token))?;
+        query.bind((":now", now))?;
+
+        match query.next()? {
+            State::Row => {
+                let id = query.read(0)?;
+                let login = query.read(1)?;
+                let household = query.read(2)?;
+                let role = query.read(3)?;
+
+                Ok(Some(UserData::new(id, login, household, role)))
+            }
+            State::Done => Ok(None),
+        }
+    }
+
+    /// Removes the session and all sessions which have expired at `now`.
+    pub async fn remove_session(&self, token: &str, now: i64) -> Result<(), Error> {
+        debug!("Remove session");
+
+        let lock = self.inner.lock().await;
+        let mut query =
+            lock.prepare("DELETE FROM sessions WHERE token = :token OR expires <= :now")?;
+        query.bind((":token", token))?;
+        query.bind((":now", now))?;
+        query.next()?;
+
+        Ok(())
+    }
+
+    pub async fn insert_token(
+        &self,
+        user: i64,
+        name: &str,
+        token: &str,
+        scope: &str,
+        created: i64,
+    ) -> Result<i64, Error> {
+        debug!(
+            "Insert token: user = {}, name = {}, scope = {}",
+            user, name, scope
+        );
+
+        let lock = self.inner.lock().await;
+        let mut query = lock.prepare(
+            "INSERT INTO tokens (user, name, token, scope, created)
+            VALUES (:user, :name, :token, :scope, :created)",
+        )?;
+        query.bind((":user", user))?;
+        query.bind((":name", name))?;
+        query.bind((":token", token))?;
+        query.bind((":scope", scope))?;
+        query.bind((":created", created))?;
+        query.next()?;
+
+        last_insert_id(&lock)
+    }
+
+    pub async fn select_tokens(&self, user: i64) -> Result<Vec<TokenData>, Error> {
+        debug!("Tokens: {}", user);
+
+        let lock = self.inner.lock().await;
+        let mut query = lock.prepare(
+            "SELECT id, name, scope, created, used FROM tokens WHERE user = :user ORDER BY id",
+        )?;
+        query.bind((":user", user))?;
+
+        let mut result = Vec::new();
+
+        while let State::Row = query.next()? {
+            let id = query.read(0)?;
+            let name = query.read(1)?;
+            let scope = query.read(2)?;
+            let created = query.read(3)?;
+            let used = query.read(4)?;
+
+            result.push(TokenData::new(id, name, scope, created, used));
+        }
+
+        Ok(result)
+    }
+
+    pub async fn remove_token(&self, user: i64, id: i64) -> Result<(), Error> {
+        debug!("Remove token: user = {}, id = {}", user, id);
+
+        let lock = self.inner.lock().await;
+        let mut query = lock.prepare("DELETE FROM tokens WHERE id = :id AND user = :user")?;
+        query.bind((":id", id))?;
+        query.bind((":user", user))?;
+        query.next()?;
+
+        if lock.change_count() == 0 {
+            return Err(Error::NotFound(format!("Token {} not found", id)));
+        }
+
+        Ok(())
+    }
+
+    /// Selects the user and the scope of a token and marks the token as used
+    /// at `now`.
+    pub async fn select_token_user(
+        &self,
+        token: &str,
+        now: i64,
+    ) -> Result<Option<(UserData, String)>, Error> {
+        debug!("Token user");
+
+        let lock = self.inner.lock().await;
+        let mut query = lock.prepare(
+            "SELECT u.id, u.login, u.household, u.role, t.scope
+            FROM tokens AS t INNER JOIN users AS u ON (u.id = t.user)
+            WHERE t.token = :token",
+        )?;
+        query.bind((":token", token))?;
+
+        let result = match query.next()? {
+            State::Row => {
+                let id = query.read(0)?;
+                let login = query.read(1)?;
+                let household = query.read(2)?;
+                let role = query.read(3)?;
+                let user = UserData::new(id, login, household, role);
+
+                (user, query.read(4)?)
+            }
+            State::Done => return Ok(None),
+        };
+
+        drop(query);
+
+        let mut query = lock.prepare("UPDATE tokens SET used = :used WHERE token = :token")?;
+        query.bind((":token", token))?;
+        query.bind((":used", now))?;
+        query.next()?;
+
+        Ok(Some(result))
+    }
+
+    pub async fn select_people(&self, owner: i64) -> Result<Vec<PersonData>, Error> {
+        debug!("People: {}", owner);
+
+        let lock = self.inner.lock().await;
+        let mut query =
+            lock.prepare("SELECT id, name FROM people WHERE owner = :owner ORDER BY name")?;
+        query.bind((":owner", owner))?;
+
+        let mut result = Vec::new();
+
+        while let State::Row = query.next()? {
+            result.push(PersonData::new(query.read(0)?, query.read(1)?));
+        }
+
+        Ok(result)
+    }
+
+    pub async fn insert_person(&self, owner: i64, name: &str) -> Result<i64, Error> {
+        debug!("Insert person: owner = {}, name = {}", owner, name);
+
+        let lock = self.inner.lock().await;
+
+        transaction(&lock, |connection| {
+            let mut query = connection
+                .prepare("SELECT id FROM people WHERE owner = :owner AND name = :name")?;
+            query.bind((":owner", owner))?;
+            query.bind((":name", name))?;
+
+            if let State::Row = query.next()? {
+                return Err(Error::Duplicate(format!("Person {} already exists", name)));
+            }
+
+            let mut query =
+                connection.prepare("INSERT INTO people (owner, name) VALUES (:owner, :name)")?;
+            query.bind((":owner", owner))?;
+            query.bind((":name", name))?;
+            query.next()?;
+
+            last_insert_id(connection)
+        })
+    }
+
+    /// Removes the person together with the person's shares and payments.
+    pub async fn remove_person(&self, owner: i64, id: i64) -> Result<(), Error> {
+        debug!("Remove person: owner = {}, id = {}", owner, id);
+
+        let lock = self.inner.lock().await;
+
+        transaction(&lock, |connection| {
+            check_person_owner(connection, owner, id)?;
+
+            let mut query = connection.prepare("DELETE FROM splits WHERE person = :person")?;
+            query.bind((":person", id))?;
+            query.next()?;
+
+            let mut query = connection.prepare("DELETE FROM payments WHERE person = :person")?;
+            query.bind((":person", id))?;
+            query.next()?;
+
+            let mut query = connection.prepare("DELETE FROM people WHERE id = :id")?;
+            query.bind((":id", id))?;
+            query.next()?;
+
+            Ok(())
+        })
+    }
+
+    /// Selects receipts with their items, shares of the items and payers,
+    /// newest receipts go first.
+    pub async fn select_split_receipts(&self, owner: i64) -> Result<Vec<SplitReceiptData>, Error> {
+        debug!("Split receipts: {}", owner);
+
+        let lock = self.inner.lock().await;
+        let mut query = lock.prepare(
+            "SELECT t.ticket, t.date, r.seller, p.person, t.id, t.product, t.sum, s.person, s.share
+            FROM tickets AS t
+                LEFT OUTER JOIN receipts AS r ON (r.owner = t.owner AND r.ticket = t.ticket)
+                LEFT OUTER JOIN payments AS p ON (p.owner = t.owner AND p.ticket = t.ticket)
+                LEFT OUTER JOIN splits AS s ON (s.item = t.id)
+            WHERE t.owner = :owner
+            ORDER BY t.date DESC, t.ticket, t.id, s.person",
+        )?;
+        query.bind((":owner", owner))?;
+
+        let mut result: Vec<SplitReceiptData> = Vec::new();
+
+        while let State::Row = query.next()? {
+            let ticket: String = query.read(0)?;
+
+            if result.last().map(|receipt| receipt.ticket()) != Some(ticket.as_str()) {
+                let date = query.read(1)?;
+                let seller = query.read(2)?;
+                let payer = query.read(3)?;
+
+                result.push(SplitReceiptData::new(ticket, date, seller, payer));
+            }
+
+            let receipt = match result.last_mut() {
+                Some(receipt) => receipt,
+                None => continue,
+            };
+            let id: i64 = query.read(4)?;
+
+            if receipt.items().last().map(|item| item.id()) != Some(id) {
+                let product = query.read(5)?;
+                let sum = query.read(6)?;
+
+                receipt.push_item(SplitItemData::new(id, product, sum));
+            }
+
+            let person: Option<i64> = query.read(7)?;
+            let share: Option<f64> = query.read(8)?;
+
+            if let (Some(person), Some(share), Some(item)) =
+                (person, share, receipt.last_item_mut())
+            {
+                item.push_share(person, share);
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Replaces the shares of a receipt item, an item without shares is not
+    /// split between people.
+    pub async fn update_item_shares(
+        &self,
+        owner: i64,
+        item: i64,
+        shares: &[(i64, f64)],
+    ) -> Result<(), Error> {
+        debug!(
+            "Update item shares: owner = {}, item = {}, shares = {:?}",
+            owner, item, shares
+        );
+
+        let lock = self.inner.lock().await;
+
+        transaction(&lock, |connection| {
+            let mut query = connection
+                .prepare("SELECT COUNT(*) FROM tickets WHERE id = :item AND owner = :owner")?;
+            query.bind((":item", item))?;
+            query.bind((":owner", owner))?;
+            query.next()?;
+
+            if query.read::<i64, _>(0)? == 0 {
+                return Err(Error::NotFound(format!("Receipt item {} not found", item)));
+            }
+
+            let mut query = connection.prepare("DELETE FROM splits WHERE item = :item")?;
+            query.bind((":item", item))?;
+            query.next()?;
+
+            for (person, share) in shares {
+                check_person_owner(connection, owner, *person)?;
+
+                if !share.is_finite() || *share <= 0.0 {
+                    return Err(Error::Invalid(format!("Invalid share: {}", share)));
+                }
+
+                let mut query = connection.prepare(
+                    "INSERT INTO splits (item, person, share) VALUES (:item, :person, :share)",
+                )?;
+                query.bind((":item", item))?;
+                query.bind((":person", *person))?;
+                query.bind((":share", *share))?;
+                query.next()?;
+            }
+
+            Ok(())
+        })
+    }
+
+    pub async fn update_receipt_payer(
+        &self,
+        owner: i64,
+        ticket: &str,
+        person: Option<i64>,
+    ) -> Result<(), Error> {
+        debug!(
+            "Update receipt payer: owner = {}, ticket = {}, person = {:?}",
+            owner, ticket, person
+        );
+
+        let lock = self.inner.lock().await;
+
+        transaction(&lock, |connection| {
+            check_ticket_owner(connection, owner, ticket)?;
+
+            let mut query = connection
+                .prepare("DELETE FROM payments WHERE owner = :owner AND ticket = :ticket")?;
+            query.bind((":owner", owner))?;
+            query.bind((":ticket", ticket))?;
+            query.next()?;
+
+            if let Some(person) = person {
+                check_person_owner(connection, owner, person)?;
+
+                let mut query = connection.prepare(
+                    "INSERT INTO payments (owner, ticket, person) VALUES (:owner, :ticket, :person)",
+                )?;
+                query.bind((":owner", owner))?;
+                query.bind((":ticket", ticket))?;
+                query.bind((":person", person))?;
+                query.next()?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+fn migrate(connection: &Connection) -> Result<(), Error> {
+    let mut query = connection.prepare("PRAGMA user_version")?;
+    let version = match query.next()? {
+        State::Row => query.read::<i64, _>(0)? as usize,
+        State::Done => 0,
+    };
+    drop(query);
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        info!("Applying database migration {}", index + 1);
+
+        transaction(connection, |connection| {
+            connection.execute(migration)?;
+            connection.execute(format!("PRAGMA user_version = {}", index + 1))?;
+
+            Ok(())
+        })?;
+    }
+
+    Ok(())
+}
+
+fn transaction<T, F>(connection: &Connection, callback: F) -> Result<T, Error>
+where
+    F: FnOnce(&Connection) -> Result<T, Error>,
+{
+    connection.execute("BEGIN")?;
+
+    match callback(connection) {
+        Ok(result) => {
+            connection.execute("COMMIT")?;
+
+            Ok(result)
+        }
+        Err(error) => {
+            connection.execute("ROLLBACK")?;
+
+            Err(error)
+        }
+    }
+}
+
+fn last_insert_id(connection: &Connection) -> Result<i64, Error> {
+    let mut query = connection.prepare("SELECT last_insert_rowid()")?;
+    query.next()?;
+
+    Ok(query.read(0)?)
+}
+
+fn select_user_password(
+    connection: &Connection,
+    login: &str,
+) -> Result<Option<(UserData, String)>, Error> {
+    let mut query = connection
+        .prepare("SELECT id, login, household, role, password FROM users WHERE login = :login")?;
+    query.bind((":login", login))?;
+
+    match query.next()? {
+        State::Row => {
+            let id = query.read(0)?;
+            let login = query.read(1)?;
+            let household = query.read(2)?;
+            let role = query.read(3)?;
+            let user = UserData::new(id, login, household, role);
+
+            Ok(Some((user, query.read(4)?)))
+        }
+        State::Done => Ok(None),
+    }
+}
+
+fn insert_user(
+    connection: &Connection,
+    login: &str,
+    password: &str,
+    household: i64,
+    role: &str,
+) -> Result<UserData, Error> {
+    if select_user_password(connection, login)?.is_some() {
+        return Err(Error::Duplicate(format!("User {} already exists", login)));
+    }
+
+    let mut query = connection.prepare(
+        "INSERT INTO users (login, password, household, role)
+        VALUES (:login, :password, :household, :role)",
+    )?;
+    query.bind((":login", login))?;
+    query.bind((":password", password))?;
+    query.bind((":household", household))?;
+    query.bind((":role", role))?;
+    query.next()?;
+
+    let id = last_insert_id(connection)?;
+
+    Ok(UserData::new(id, login.into(), household, role.into()))
+}
+
+fn check_ticket_owner(connection: &Connection, owner: i64, ticket: &str) -> Result<(), Error> {
+    let mut query = connection
+        .prepare("SELECT COUNT(*) FROM tickets WHERE ticket = :ticket AND owner = :owner")?;
+    query.bind((":ticket", ticket))?;
+    query.bind((":owner", owner))?;
+    query.next()?;
+
+    match query.read::<i64, _>(0)? {
+        0 => Err(Error::NotFound(format!("Receipt {} not found", ticket))),
+        _ => Ok(()),
+    }
+}
+
+fn check_person_owner(connection: &Connection, owner: i64, id: i64) -> Result<(), Error> {
+    let mut query =
+        connection.prepare("SELECT COUNT(*) FROM people WHERE id = :id AND owner = :owner")?;
+    query.bind((":id", id))?;
+    query.bind((":owner", owner))?;
+    query.next()?;
+
+    match query.read::<i64, _>(0)? {
+        0 => Err(Error::NotFound(format!("Person {} not found", id))),
+        _ => Ok(()),
+    }
+}
+
+/// Fails with `NotFound` when the household does not exist.
+fn check_household(connection: &Connection, household: i64) -> Result<(), Error> {
+    let mut query = connection.prepare("SELECT COUNT(*) FROM households WHERE id = :id")?;
+    query.bind((":id", household))?;
+    query.next()?;
+
+    match query.read::<i64, _>(0)? {
+        0 => Err(Error::NotFound(format!(
+            "Household {} not found",
+            household
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Fails when the household is left without an administrator.
+fn check_household_admin(connection: &Connection, household: i64) -> Result<(), Error> {
+    let mut query = connection
+        .prepare("SELECT COUNT(*) FROM users WHERE household = :household AND role = :role")?;
+    query.bind((":household", household))?;
+    query.bind((":role", ROLE_ADMIN))?;
+    query.next()?;
+
+    match query.read::<i64, _>(0)? {
+        0 => Err(Error::Invalid(
+            "Household must have at least one admin".into(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn find_category_node(
+    connection: &Connection,
+    owner: i64,
+    parent: Option<i64>,
+    name: &str,
+) -> Result<Option<i64>, Error> {
+    let mut query = connection.prepare(
+        "SELECT id FROM category_nodes WHERE owner = :owner AND parent IS :parent AND name = :name",
+    )?;
+    query.bind((":owner", owner))?;
+    query.bind((":parent", parent))?;
+    query.bind((":name", name))?;
+
+    match query.next()? {
+        State::Row => Ok(Some(query.read(0)?)),
+        State::Done => Ok(None),
+    }
+}
+
+fn split_category_path(category: &str) -> Vec<&str> {
+    category
+        .split(CATEGORY_SEPARATOR.trim())
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+fn validate_category_name(name: &str) -> Result<(), Error> {
+    if name.is_empty() || name.contains(CATEGORY_SEPARATOR.trim()) {
+        return Err(Error::Invalid(format!("Invalid category name: {:?}", name)));
+    }
+
+    Ok(())
+}
+
+fn insert_product_node(
+    connection: &Connection,
+    owner: i64,
+    product: &str,
+    node: i64,
+) -> Result<(), Error> {
+    let mut query = connection.prepare(
+        "INSERT OR REPLACE INTO products (owner, product, node) VALUES (:owner, :product, :node)",
+    )?;
+    query.bind((":owner", owner))?;
+    query.bind((":product", product))?;
+    query.bind((":node", node))?;
+    query.next()?;
+
+    Ok(())
+}
+
+fn insert_category_node(
+    connection: &Connection,
+    owner: i64,
+    parent: Option<i64>,
+    name: &str,
+) -> Result<i64, Error> {
+    validate_category_name(name)?;
+
+    let mut query = connection.prepare(
+        "INSERT INTO category_nodes (owner, parent, name) VALUES (:owner, :parent, :name)",
+    )?;
+    query.bind((":owner", owner))?;
+    query.bind((":parent", parent))?;
+    query.bind((":name", name))?;
+    query.next()?;
+
+    last_insert_id(connection)
+}
+
+fn resolve_category_path(connection: &Connection, owner: i64, path: &[&str]) -> Result<i64, Error> {
+    let mut parent = None;
+
+    for name in path {
+        let node = match find_category_node(connection, owner, parent, name)? {
+            Some(node) => node,
+            None => insert_category_node(connection, owner, parent, name)?,
+        };
+
+        parent = Some(node);
+    }
+
+    parent.ok_or_else(|| Error::Invalid("Empty category path".into()))
+}
+
+fn find_category_path(connection: &Connection, owner: i64, path: &[&str]) -> Result<i64, Error> {
+    let mut parent = None;
+
+    for name in path {
+        match find_category_node(connection, owner, parent, name)? {
+            Some(node) => parent = Some(node),
+            None => {
+                return Err(Error::NotFound(format!(
+                    "Category {} not found",
+                    path.join(CATEGORY_SEPARATOR)
+                )))
+            }
+        }
+    }
+
+    parent.ok_or_else(|| Error::Invalid("Empty category path".into()))
+}
+
+fn select_category_parent(
+    connection: &Connection,
+    owner: i64,
+    id: i64,
+) -> Result<Option<i64>, Error> {
+    let mut query = connection
+        .prepare("SELECT parent FROM category_nodes WHERE id = :id AND owner = :owner")?;
+    query.bind((":id", id))?;
+    query.bind((":owner", owner))?;
+
+    match query.next()? {
+        State::Row => Ok(query.read(0)?),
+        State::Done => Err(Error::NotFound(format!("Category {} not found", id))),
+    }
+}
+
+fn select_category_name(connection: &Connection, owner: i64, id: i64) -> Result<String, Error> {
+    let mut query =
+        connection.prepare("SELECT name FROM category_nodes WHERE id = :id AND owner = :owner")?;
+    query.bind((":id", id))?;
+    query.bind((":owner", owner))?;
+
+    match query.next()? {
+        State::Row => Ok(query.read(0)?),
+        State::Done => Err(Error::NotFound(format!("Category {} not found", id))),
+    }
+}
+
+/// Checks whether `node` is `ancestor` itself or lies in its subtree.
+fn is_category_descendant(
+    connection: &Connection,
+    owner: i64,
+    node: i64,
+    ancestor: i64,
+) -> Result<bool, Error> {
+    let mut current = Some(node);
+
+    while let Some(id) = current {
+        if id == ancestor {
+            return Ok(true);
+        }
+
+        current = select_category_parent(connection, owner, id)?;
+    }
+
+    Ok(false)
+}
+
+fn merge_category_nodes(
+    connection: &Connection,
+    owner: i64,
+    source: i64,
+    target: i64,
+) -> Result<(), Error> {
+    let mut query =
+        connection.prepare("SELECT id, name FROM category_nodes WHERE parent = :parent")?;
+    query.bind((":parent", source))?;
+
+    let mut children: Vec<(i64, String)> = Vec::new();
+
+    while let State::Row = query.next()? {
+        children.push((query.read(0)?, query.read(1)?));
+    }
+
+    drop(query);
+
+    for (child, name) in children {
+        match find_category_node(connection, owner, Some(target), &name)? {
+            Some(existing) => merge_category_nodes(connection, owner, child, existing)?,
+            None => {
+                let mut query = connection
+                    .prepare("UPDATE category_nodes SET parent = :parent WHERE id = :id")?;
+                query.bind((":id", child))?;
+                query.bind((":parent", target))?;
+                query.next()?;
+            }
+        }
+    }
+
+    let mut query =
+        connection.prepare("UPDATE products SET node = :target WHERE node = :source")?;
+    query.bind((":source", source))?;
+    query.bind((":target", target))?;
+    query.next()?;
+
+    let mut query =
+        connection.prepare("UPDATE OR IGNORE budgets SET node = :target WHERE node = :source")?;
+    query.bind((":source", source))?;
+    query.bind((":target", target))?;
+    query.next()?;
+
+    let mut query = connection.prepare("DELETE FROM budgets WHERE node = :id")?;
+    query.bind((":id", source))?;
+    query.next()?;
+
+    let mut query = connection.prepare("DELETE FROM category_nodes WHERE id = :id")?;
+    query.bind((":id", source))?;
+    query.next()?;
+
+    Ok(())
 }
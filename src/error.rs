@@ -0,0 +1,69 @@
+use std::fmt;
+
+/// Errors returned by the library. The message of every variant is meant to
+/// be shown to the user as is.
+#[derive(Debug)]
+pub enum Error {
+    /// Input is malformed or violates a constraint, e.g. a bad QR code string
+    /// or moving a category into itself.
+    Invalid(String),
+    /// Referenced record does not exist or belongs to another household.
+    NotFound(String),
+    /// Record with the same unique key already exists.
+    Duplicate(String),
+    /// OFD request failed or returned an unexpected response.
+    Ofd(String),
+    /// Database query failed.
+    Database(sqlite::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Invalid(message)
+            | Error::NotFound(message)
+            | Error::Duplicate(message)
+            | Error::Ofd(message) => f.write_str(message),
+            Error::Database(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Database(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<sqlite::Error> for Error {
+    fn from(value: sqlite::Error) -> Self {
+        Error::Database(value)
+    }
+}
+
+impl From<serde_qs::Error> for Error {
+    fn from(value: serde_qs::Error) -> Self {
+        Error::Invalid(format!("Invalid QR code: {}", value))
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(value: reqwest::Error) -> Self {
+        Error::Ofd(format!("OFD request failed: {}", value))
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(value: serde_json::Error) -> Self {
+        Error::Ofd(format!("Invalid OFD response: {}", value))
+    }
+}
+
+impl From<time::error::Parse> for Error {
+    fn from(value: time::error::Parse) -> Self {
+        Error::Ofd(format!("Invalid OFD receipt date: {}", value))
+    }
+}
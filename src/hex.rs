@@ -0,0 +1,4 @@
+/// Formats bytes as lowercase hexadecimal, as used for tokens and ETags.
+pub fn encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
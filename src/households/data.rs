@@ -0,0 +1,109 @@
+use crate::database::UserData;
+use serde::Deserialize;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = households::ReplyItem)]
+pub struct ReplyItem {
+    id: i64,
+    login: String,
+    role: String,
+}
+
+impl From<UserData> for ReplyItem {
+    fn from(value: UserData) -> Self {
+        Self {
+            id: value.id(),
+            login: value.login().into(),
+            role: value.role().into(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(untagged)]
+#[schema(as = households::Reply)]
+pub enum Reply {
+    List {
+        success: bool,
+        items: Vec<ReplyItem>,
+    },
+    Created {
+        success: bool,
+        id: i64,
+    },
+    Success {
+        success: bool,
+    },
+}
+
+impl Reply {
+    pub fn list(items: Vec<UserData>) -> Self {
+        let items = items.into_iter().map(ReplyItem::from).collect();
+
+        Reply::List {
+            success: true,
+            items,
+        }
+    }
+
+    pub fn created(id: i64) -> Self {
+        Reply::Created { success: true, id }
+    }
+
+    pub fn success() -> Self {
+        Reply::Success { success: true }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[schema(as = households::CreateParams)]
+pub struct CreateParams {
+    login: String,
+    password: String,
+    role: String,
+}
+
+impl CreateParams {
+    pub fn login(&self) -> &str {
+        self.login.trim()
+    }
+
+    pub fn password(&self) -> &str {
+        &self.password
+    }
+
+    pub fn role(&self) -> &str {
+        &self.role
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = households::RoleParams)]
+pub struct RoleParams {
+    id: i64,
+    role: String,
+}
+
+impl RoleParams {
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+
+    pub fn role(&self) -> &str {
+        &self.role
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = households::DeleteParams)]
+pub struct DeleteParams {
+    id: i64,
+}
+
+impl DeleteParams {
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+}
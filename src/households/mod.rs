@@ -0,0 +1,124 @@
+mod data;
+
+pub use self::data::CreateParams;
+pub use self::data::DeleteParams;
+pub use self::data::Reply;
+pub use self::data::RoleParams;
+
+use crate::api_error::OrReject;
+use crate::database::Database;
+use crate::database::UserData;
+use crate::database::ROLE_ADMIN;
+use crate::database::ROLE_MEMBER;
+use crate::users;
+use receipt_check::Error;
+use warp::Rejection;
+
+/// Lists members of the household.
+#[utoipa::path(
+    get,
+    path = "/api/v1/household/members",
+    tag = "household",
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn members(user: UserData, database: Database) -> Result<impl warp::Reply, Rejection> {
+    info!("Request household members: {}", user.household());
+
+    let items = database
+        .select_members(user.household())
+        .await
+        .or_reject("Failed to read members")?;
+
+    Ok(warp::reply::json(&Reply::list(items)))
+}
+
+/// Adds a member account to the household.
+#[utoipa::path(
+    post,
+    path = "/api/v1/household/members",
+    tag = "household",
+    request_body = CreateParams,
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn create(
+    user: UserData,
+    params: CreateParams,
+    database: Database,
+) -> Result<impl warp::Reply, Rejection> {
+    info!(
+        "Request household member create: household = {}, login = {}, role = {}",
+        user.household(),
+        params.login(),
+        params.role()
+    );
+
+    validate_role(params.role()).or_reject("Invalid role")?;
+    users::validate(params.login(), params.password()).or_reject("Invalid member")?;
+
+    let password = users::hash_password(params.password()).or_reject("Failed to hash password")?;
+    let member = database
+        .insert_member(user.household(), params.login(), &password, params.role())
+        .await
+        .or_reject("Failed to save member")?;
+
+    Ok(warp::reply::json(&Reply::created(member.id())))
+}
+
+/// Changes the role of a member, `id` is taken from the path.
+#[utoipa::path(
+    put,
+    path = "/api/v1/household/members/{id}/role",
+    tag = "household",
+    params(
+        ("id" = i64, Path, description = "Member id"),
+    ),
+    request_body = RoleParams,
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn role(
+    user: UserData,
+    params: RoleParams,
+    database: Database,
+) -> Result<impl warp::Reply, Rejection> {
+    info!("Request household member role: {:?}", params);
+
+    validate_role(params.role()).or_reject("Invalid role")?;
+    database
+        .update_member_role(user.household(), params.id(), params.role())
+        .await
+        .or_reject("Failed to save role")?;
+
+    Ok(warp::reply::json(&Reply::success()))
+}
+
+/// Removes a member from the household.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/household/members/{id}",
+    tag = "household",
+    params(
+        ("id" = i64, Path, description = "Member id"),
+    ),
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn delete(
+    user: UserData,
+    params: DeleteParams,
+    database: Database,
+) -> Result<impl warp::Reply, Rejection> {
+    info!("Request household member delete: {:?}", params);
+
+    database
+        .remove_member(user.household(), params.id())
+        .await
+        .or_reject("Failed to delete member")?;
+
+    Ok(warp::reply::json(&Reply::success()))
+}
+
+fn validate_role(role: &str) -> Result<(), Error> {
+    match role {
+        ROLE_ADMIN | ROLE_MEMBER => Ok(()),
+        _ => Err(Error::Invalid(format!("Unknown role: {}", role))),
+    }
+}
@@ -0,0 +1,37 @@
+//! Receipt Check library: loads Russian fiscal receipts from the OFD by the
+//! QR code string printed on a receipt and stores them in an SQLite database
+//! shared by households.
+//!
+//! ```no_run
+//! use receipt_check::ofd::{load_params, Provider};
+//! use std::time::Duration;
+//!
+//! # async fn example() -> Result<(), receipt_check::Error> {
+//! let provider = Provider::new(
+//!     "https://consumer.1-ofd.ru/api/tickets/ticket",
+//!     Duration::from_secs(30),
+//! )?;
+//! let params = load_params("t=20240101T1200&s=100.00&fn=1&i=2&fp=3&n=1").await?;
+//! let ticket = provider.load_ticket(&params).await?;
+//!
+//! for item in ticket.items() {
+//!     println!("{} {}", item.name(), item.sum());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+#[macro_use]
+extern crate log;
+
+pub mod database;
+pub mod error;
+pub mod normalizer;
+pub mod ofd;
+
+pub use crate::database::Database;
+pub use crate::error::Error;
+pub use crate::ofd::Provider;
+pub use crate::ofd::Ticket;
+pub use crate::ofd::TicketItem;
+pub use crate::ofd::TicketParams;
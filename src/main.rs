@@ -1,76 +1,503 @@
 #[macro_use]
 extern crate log;
 
+mod api_error;
+mod assets;
+mod basic_auth;
+mod budgets;
 mod categories;
-mod database;
-mod ofd;
+mod certificate;
+mod classifier;
+mod commands;
+mod hex;
+mod households;
+mod openapi;
 mod options;
+mod products;
+mod proxy;
 mod qrcode;
+mod rest;
+mod rules;
+mod server;
+mod splits;
+mod stores;
 mod tickets;
+mod tokens;
+mod users;
+mod v1;
 
+use crate::basic_auth::BasicAuth;
+use crate::classifier::Classifier;
 use crate::database::Database;
-use options::Options;
+use crate::database::SCOPE_FULL;
+use crate::database::SCOPE_READ;
+use crate::database::SCOPE_SCAN;
+use crate::ofd::Provider;
+use crate::options::Command;
+use crate::options::ConfigCommand;
+use crate::options::Options;
+use crate::proxy::Proxies;
+use crate::server::Tls;
+use receipt_check::database;
+use receipt_check::normalizer;
+use receipt_check::ofd;
 use std::convert::Infallible;
 use std::error::Error;
-use structopt::StructOpt;
+use std::net::SocketAddr;
+use std::path::Path;
 use warp::Filter;
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn Error>> {
-    env_logger::init();
+fn main() -> Result<(), Box<dyn Error>> {
+    budgets::init_local_offset();
 
-    info!("Parsing options...");
+    tokio::runtime::Runtime::new()?.block_on(run())
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let options = Options::load()?;
+    let mut logger = env_logger::Builder::from_default_env();
+
+    if let Some(filters) = options.log() {
+        logger.parse_filters(filters);
+    }
 
-    let options = Options::from_args();
+    logger.init();
+    info!("Local time offset: {}", budgets::local_offset());
+
+    if let Some(Command::Config(ConfigCommand::Check)) = options.command() {
+        return check(&options);
+    }
 
     info!("Setup database...");
 
     let database = Database::new(options.database())?;
 
+    match options.command() {
+        None | Some(Command::Serve) => {}
+        Some(command) => return commands::run(command, &options, &database).await,
+    }
+
+    let basic_auth = BasicAuth::new(&options)?;
+    let provider = Provider::new(options.ofd_url(), options.ofd_timeout())?;
+
+    if basic_auth.is_enabled() {
+        info!("HTTP Basic authentication enabled");
+    }
+
+    info!("Normalize products...");
+
+    let products = database.select_uncanonical_products().await?;
+    products::canonicalize(&database, &products).await?;
+
+    for owner in database.select_product_owners().await? {
+        products::inherit_categories(&database, owner).await?;
+    }
+
+    products::measure(&database).await?;
+
+    info!("Train classifier...");
+
+    let classifier = Classifier::new();
+
+    for owner in database.select_product_owners().await? {
+        classifier
+            .train(owner, &database.select_products(owner).await?)
+            .await;
+    }
+
     info!("Create routes...");
 
-    let index = warp::get()
-        .and(warp::path::end())
-        .and(warp::fs::file("public/scanner.html"));
+    let proxies = Proxies::new(&options);
+    let index = assets::index(options.public_dir());
+    let v1 = v1::routes(&database, &classifier, &provider, &proxies);
+    let users_register = warp::path!("api" / "users" / "register")
+        .and(warp::post())
+        .and(proxy::forwarded(proxies.clone()))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and_then(users::register);
+    let users_login = warp::path!("api" / "users" / "login")
+        .and(warp::post())
+        .and(proxy::forwarded(proxies.clone()))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and_then(users::login);
+    let users_logout = warp::path!("api" / "users" / "logout")
+        .and(warp::post())
+        .and(proxy::forwarded(proxies.clone()))
+        .and(warp::cookie::optional(users::SESSION_COOKIE))
+        .and(with(database.clone()))
+        .and_then(users::logout);
+    let users_me = warp::path!("api" / "users" / "me")
+        .and(warp::post())
+        .and(users::user(database.clone(), SCOPE_READ))
+        .and_then(users::me);
+    let households_members = warp::path!("api" / "households" / "members")
+        .and(warp::post())
+        .and(users::user(database.clone(), SCOPE_READ))
+        .and(with(database.clone()))
+        .and_then(households::members);
+    let households_create = warp::path!("api" / "households" / "create")
+        .and(warp::post())
+        .and(users::admin(database.clone()))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and_then(households::create);
+    let households_role = warp::path!("api" / "households" / "role")
+        .and(warp::post())
+        .and(users::admin(database.clone()))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and_then(households::role);
+    let households_delete = warp::path!("api" / "households" / "delete")
+        .and(warp::post())
+        .and(users::admin(database.clone()))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and_then(households::delete);
+    let tokens_list = warp::path!("api" / "tokens" / "list")
+        .and(warp::post())
+        .and(users::user(database.clone(), SCOPE_FULL))
+        .and(with(database.clone()))
+        .and_then(tokens::list);
+    let tokens_create = warp::path!("api" / "tokens" / "create")
+        .and(warp::post())
+        .and(users::user(database.clone(), SCOPE_FULL))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and_then(tokens::create);
+    let tokens_delete = warp::path!("api" / "tokens" / "delete")
+        .and(warp::post())
+        .and(users::user(database.clone(), SCOPE_FULL))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and_then(tokens::delete);
     let qrcode = warp::path!("api" / "qrcode")
         .and(warp::post())
+        .and(users::user(database.clone(), SCOPE_SCAN))
         .and(warp::body::json())
         .and(with(database.clone()))
+        .and(with(provider))
         .and_then(qrcode::qrcode);
     let tickets_list = warp::path!("api" / "tickets" / "list")
         .and(warp::post())
+        .and(users::user(database.clone(), SCOPE_READ))
         .and(with(database.clone()))
         .and_then(tickets::list);
     let tickets_clear = warp::path!("api" / "tickets" / "clear")
         .and(warp::post())
+        .and(users::admin(database.clone()))
         .and(with(database.clone()))
         .and_then(tickets::clear);
+    let tickets_delete = warp::path!("api" / "tickets" / "delete")
+        .and(warp::post())
+        .and(users::admin(database.clone()))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and_then(tickets::delete);
     let categories_list = warp::path!("api" / "categories" / "list")
         .and(warp::post())
+        .and(users::user(database.clone(), SCOPE_READ))
         .and(with(database.clone()))
+        .and(with(classifier.clone()))
         .and_then(categories::list);
     let categories_update = warp::path!("api" / "categories" / "update")
         .and(warp::post())
+        .and(users::admin(database.clone()))
         .and(warp::body::json())
         .and(with(database.clone()))
+        .and(with(classifier.clone()))
         .and_then(categories::update);
-    let public = warp::get().and(warp::fs::dir("public"));
-    let routes = index
-        .or(qrcode)
+    let categories_tree = warp::path!("api" / "categories" / "tree")
+        .and(warp::post())
+        .and(users::user(database.clone(), SCOPE_READ))
+        .and(with(database.clone()))
+        .and_then(categories::tree);
+    let categories_create = warp::path!("api" / "categories" / "create")
+        .and(warp::post())
+        .and(users::admin(database.clone()))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and_then(categories::create);
+    let categories_rename = warp::path!("api" / "categories" / "rename")
+        .and(warp::post())
+        .and(users::admin(database.clone()))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and(with(classifier.clone()))
+        .and_then(categories::rename);
+    let categories_move = warp::path!("api" / "categories" / "move")
+        .and(warp::post())
+        .and(users::admin(database.clone()))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and(with(classifier.clone()))
+        .and_then(categories::move_node);
+    let categories_merge = warp::path!("api" / "categories" / "merge")
+        .and(warp::post())
+        .and(users::admin(database.clone()))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and(with(classifier.clone()))
+        .and_then(categories::merge);
+    let categories_assign = warp::path!("api" / "categories" / "assign")
+        .and(warp::post())
+        .and(users::admin(database.clone()))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and(with(classifier.clone()))
+        .and_then(categories::assign);
+    let categories_path_rename = warp::path!("api" / "categories" / "path" / "rename")
+        .and(warp::post())
+        .and(users::admin(database.clone()))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and(with(classifier.clone()))
+        .and_then(categories::rename_path);
+    let categories_path_merge = warp::path!("api" / "categories" / "path" / "merge")
+        .and(warp::post())
+        .and(users::admin(database.clone()))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and(with(classifier.clone()))
+        .and_then(categories::merge_path);
+    let categories_name_rename = warp::path!("api" / "categories" / "name" / "rename")
+        .and(warp::post())
+        .and(users::admin(database.clone()))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and(with(classifier.clone()))
+        .and_then(categories::rename_name);
+    let products_list = warp::path!("api" / "products" / "list")
+        .and(warp::post())
+        .and(users::user(database.clone(), SCOPE_READ))
+        .and(with(database.clone()))
+        .and_then(products::list);
+    let products_prices = warp::path!("api" / "products" / i64 / "prices")
+        .and(warp::post())
+        .and(users::user(database.clone(), SCOPE_READ))
+        .and(with(database.clone()))
+        .and_then(products::prices);
+    let products_inflation = warp::path!("api" / "products" / "inflation")
+        .and(warp::post())
+        .and(users::user(database.clone(), SCOPE_READ))
+        .and(with(database.clone()))
+        .and_then(products::inflation);
+    let stores_compare = warp::path!("api" / "stores" / "compare")
+        .and(warp::post())
+        .and(users::user(database.clone(), SCOPE_READ))
+        .and(with(database.clone()))
+        .and_then(stores::compare);
+    let budgets_list = warp::path!("api" / "budgets" / "list")
+        .and(warp::post())
+        .and(users::user(database.clone(), SCOPE_READ))
+        .and(with(database.clone()))
+        .and_then(budgets::list);
+    let budgets_update = warp::path!("api" / "budgets" / "update")
+        .and(warp::post())
+        .and(users::user(database.clone(), SCOPE_FULL))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and_then(budgets::update);
+    let budgets_delete = warp::path!("api" / "budgets" / "delete")
+        .and(warp::post())
+        .and(users::user(database.clone(), SCOPE_FULL))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and_then(budgets::delete);
+    let splits_people = warp::path!("api" / "splits" / "people")
+        .and(warp::post())
+        .and(users::user(database.clone(), SCOPE_READ))
+        .and(with(database.clone()))
+        .and_then(splits::people);
+    let splits_people_create = warp::path!("api" / "splits" / "people" / "create")
+        .and(warp::post())
+        .and(users::user(database.clone(), SCOPE_FULL))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and_then(splits::create_person);
+    let splits_people_delete = warp::path!("api" / "splits" / "people" / "delete")
+        .and(warp::post())
+        .and(users::user(database.clone(), SCOPE_FULL))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and_then(splits::delete_person);
+    let splits_receipts = warp::path!("api" / "splits" / "receipts")
+        .and(warp::post())
+        .and(users::user(database.clone(), SCOPE_READ))
+        .and(with(database.clone()))
+        .and_then(splits::receipts);
+    let splits_share = warp::path!("api" / "splits" / "share")
+        .and(warp::post())
+        .and(users::user(database.clone(), SCOPE_FULL))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and_then(splits::share);
+    let splits_payer = warp::path!("api" / "splits" / "payer")
+        .and(warp::post())
+        .and(users::user(database.clone(), SCOPE_FULL))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and_then(splits::payer);
+    let splits_balance = warp::path!("api" / "splits" / "balance")
+        .and(warp::post())
+        .and(users::user(database.clone(), SCOPE_READ))
+        .and(with(database.clone()))
+        .and_then(splits::balance);
+    let rules_list = warp::path!("api" / "rules" / "list")
+        .and(warp::post())
+        .and(users::user(database.clone(), SCOPE_READ))
+        .and(with(database.clone()))
+        .and_then(rules::list);
+    let rules_update = warp::path!("api" / "rules" / "update")
+        .and(warp::post())
+        .and(users::admin(database.clone()))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and_then(rules::update);
+    let rules_delete = warp::path!("api" / "rules" / "delete")
+        .and(warp::post())
+        .and(users::admin(database.clone()))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and_then(rules::delete);
+    let rules_preview = warp::path!("api" / "rules" / "preview")
+        .and(warp::post())
+        .and(users::user(database.clone(), SCOPE_READ))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and_then(rules::preview);
+    let rules_apply = warp::path!("api" / "rules" / "apply")
+        .and(warp::post())
+        .and(users::admin(database.clone()))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and(with(classifier.clone()))
+        .and_then(rules::apply);
+    let openapi = warp::path!("api" / "openapi.json")
+        .and(warp::get())
+        .and_then(openapi::openapi);
+    let public = assets::public(options.public_dir());
+    let users = users_register
+        .or(users_login)
+        .or(users_logout)
+        .or(users_me)
+        .or(households_members)
+        .or(households_create)
+        .or(households_role)
+        .or(households_delete)
+        .or(tokens_list)
+        .or(tokens_create)
+        .or(tokens_delete)
+        .boxed();
+    let tickets = qrcode
         .or(tickets_list)
         .or(tickets_clear)
-        .or(categories_list)
+        .or(tickets_delete)
+        .boxed();
+    let categories = categories_list
         .or(categories_update)
-        .or(public);
+        .or(categories_tree)
+        .or(categories_create)
+        .or(categories_rename)
+        .or(categories_move)
+        .or(categories_merge)
+        .or(categories_assign)
+        .or(categories_path_rename)
+        .or(categories_path_merge)
+        .or(categories_name_rename)
+        .boxed();
+    let reports = products_list
+        .or(products_prices)
+        .or(products_inflation)
+        .or(stores_compare)
+        .or(budgets_list)
+        .or(budgets_update)
+        .or(budgets_delete)
+        .boxed();
+    let splits = splits_people
+        .or(splits_people_create)
+        .or(splits_people_delete)
+        .or(splits_receipts)
+        .or(splits_share)
+        .or(splits_payer)
+        .or(splits_balance)
+        .boxed();
+    let rules = rules_list
+        .or(rules_update)
+        .or(rules_delete)
+        .or(rules_preview)
+        .or(rules_apply)
+        .boxed();
+    let deprecated = users
+        .or(tickets)
+        .or(categories)
+        .or(reports)
+        .or(splits)
+        .or(rules)
+        .with(warp::reply::with::header("deprecation", "true"))
+        .boxed();
+    let routes = basic_auth::basic_auth(basic_auth, database.clone())
+        .and(index.or(openapi).or(v1).or(deprecated).or(public))
+        .recover(api_error::recover)
+        .recover(basic_auth::recover);
 
     info!("Starting server...");
 
-    warp::serve(routes)
-        .tls()
-        .cert_path(options.certificate())
-        .key_path(options.key())
-        .run((options.address().clone(), options.port()))
-        .await;
+    let address = SocketAddr::new(*options.address(), options.port());
+    let tls = match (options.key(), options.certificate()) {
+        (Some(key), Some(certificate)) if options.tls() => {
+            certificate::ensure(key, certificate, options.address())?;
+
+            Some(Tls::new(key, certificate)?)
+        }
+        _ => {
+            info!("TLS disabled, expecting a reverse proxy");
+
+            None
+        }
+    };
+
+    server::serve(warp::service(routes), address, tls, proxies).await
+}
+
+/// Validates the configuration files referenced by options and prints the
+/// effective configuration.
+fn check(options: &Options) -> Result<(), Box<dyn Error>> {
+    let basic_auth = BasicAuth::new(options)?;
+
+    Provider::new(options.ofd_url(), options.ofd_timeout())?;
+
+    if let (Some(key), Some(certificate), true) =
+        (options.key(), options.certificate(), options.tls())
+    {
+        if key.exists() || certificate.exists() {
+            Tls::new(key, certificate)?;
+        }
+    }
+
+    if let Some(public_dir) = options.public_dir() {
+        if !public_dir.join("scanner.html").is_file() {
+            return Err(format!("Public directory {} not found", public_dir.display()).into());
+        }
+    }
+
+    let path = |path: Option<&Path>| path.map(|path| path.display().to_string());
+
+    println!("config: {:?}", path(options.config()));
+    println!("address: {}", options.address());
+    println!("port: {}", options.port());
+    println!("database: {}", options.database().display());
+    println!("public-dir: {:?}", path(options.public_dir()));
+    println!("tls: {}", options.tls());
+    println!("trusted-proxies: {:?}", options.trusted_proxies());
+    println!("key: {:?}", path(options.key()));
+    println!("certificate: {:?}", path(options.certificate()));
+    println!("ofd-url: {}", options.ofd_url());
+    println!("ofd-timeout: {:?}", options.ofd_timeout());
+    println!("basic-auth: {}", basic_auth.is_enabled());
+    println!("log: {:?}", options.log());
+    println!("Configuration is valid");
 
     Ok(())
 }
@@ -0,0 +1,160 @@
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// Canonical form of a raw product name from a receipt. Products with equal
+/// keys are considered the same goods.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Normalized {
+    key: String,
+    title: String,
+    amount: Option<f64>,
+    unit: Option<&'static str>,
+    fat: Option<f64>,
+}
+
+impl Normalized {
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Pack size in `unit`, weight is always in kilograms and volume in litres.
+    pub fn amount(&self) -> Option<f64> {
+        self.amount
+    }
+
+    pub fn unit(&self) -> Option<&'static str> {
+        self.unit
+    }
+
+    pub fn fat(&self) -> Option<f64> {
+        self.fat
+    }
+}
+
+struct Patterns {
+    decimal: Regex,
+    fat: Regex,
+    size: Regex,
+    separator: Regex,
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+
+    PATTERNS.get_or_init(|| Patterns {
+        decimal: Regex::new(r"(\d)\s*,\s*(\d)").unwrap(),
+        fat: Regex::new(r"(\d+(?:\.\d+)?)\s*%").unwrap(),
+        size: Regex::new(r"(\d+(?:\.\d+)?)\s*(кг|гр|г|мл|л|шт|kg|gr|g|ml|l|pcs)(?:[^\p{L}]|$)")
+            .unwrap(),
+        separator: Regex::new(r"[^\p{L}\p{N}]+").unwrap(),
+    })
+}
+
+/// Converts a pack size into kilograms, litres or pieces.
+fn unit_amount(value: f64, unit: &str) -> (f64, &'static str) {
+    match unit {
+        "кг" | "kg" => (value, "kg"),
+        "гр" | "г" | "gr" | "g" => (value / 1000.0, "kg"),
+        "л" | "l" => (value, "l"),
+        "мл" | "ml" => (value / 1000.0, "l"),
+        _ => (value, "pcs"),
+    }
+}
+
+fn format_number(value: f64) -> String {
+    let result = format!("{:.3}", value);
+    let result = result.trim_end_matches('0').trim_end_matches('.');
+
+    result.to_string()
+}
+
+pub fn normalize(product: &str) -> Normalized {
+    let patterns = patterns();
+    let text = product.to_lowercase().replace('ё', "е");
+    let text = patterns.decimal.replace_all(&text, "$1.$2").to_string();
+    let fat = patterns
+        .fat
+        .captures(&text)
+        .and_then(|captures| captures[1].parse().ok());
+    let text = patterns.fat.replace_all(&text, " ").to_string();
+    let size = patterns.size.captures(&text).and_then(|captures| {
+        let value: f64 = captures[1].parse().ok()?;
+
+        Some(unit_amount(value, &captures[2]))
+    });
+    let text = patterns.size.replace_all(&text, " ").to_string();
+    let words: Vec<&str> = patterns
+        .separator
+        .split(&text)
+        .filter(|word| !word.is_empty())
+        .collect();
+    let mut key = words.join(" ");
+    let mut title = key.clone();
+
+    if let Some(fat) = fat {
+        key.push_str(&format!("|{}%", format_number(fat)));
+        title.push_str(&format!(" {}%", format_number(fat)));
+    }
+
+    if let Some((amount, unit)) = size {
+        key.push_str(&format!("|{}{}", format_number(amount), unit));
+        title.push_str(&format!(" {} {}", format_number(amount), unit));
+    }
+
+    Normalized {
+        key,
+        title,
+        amount: size.map(|(amount, _)| amount),
+        unit: size.map(|(_, unit)| unit),
+        fat,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_case_decimal_separator_and_spacing() {
+        let receipt = normalize("МОЛОКО 3,2% 1Л");
+        let shelf = normalize("Молоко 3.2% 1 л");
+
+        assert_eq!(receipt, shelf);
+        assert_eq!(receipt.key(), "молоко|3.2%|1l");
+        assert_eq!(receipt.title(), "молоко 3.2% 1 l");
+        assert_eq!(receipt.fat(), Some(3.2));
+        assert_eq!(receipt.amount(), Some(1.0));
+        assert_eq!(receipt.unit(), Some("l"));
+    }
+
+    #[test]
+    fn normalize_converts_grams_and_millilitres() {
+        let cheese = normalize("Сыр Российский 200г");
+        let kefir = normalize("Кефир 1% 900 мл");
+
+        assert_eq!(cheese.key(), "сыр российский|0.2kg");
+        assert_eq!(cheese.amount(), Some(0.2));
+        assert_eq!(cheese.unit(), Some("kg"));
+        assert_eq!(kefir.key(), "кефир|1%|0.9l");
+        assert_eq!(kefir.unit(), Some("l"));
+    }
+
+    #[test]
+    fn normalize_without_size() {
+        let bread = normalize("ХЛЕБ \"Бородинский\" нарезка");
+
+        assert_eq!(bread.key(), "хлеб бородинский нарезка");
+        assert_eq!(bread.amount(), None);
+        assert_eq!(bread.unit(), None);
+        assert_eq!(bread.fat(), None);
+    }
+
+    #[test]
+    fn normalize_replaces_yo() {
+        assert_eq!(normalize("Тёртый сыр").key(), normalize("ТЕРТЫЙ СЫР").key());
+    }
+}
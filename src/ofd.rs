@@ -1,10 +1,14 @@
+use crate::error::Error;
+use crate::normalizer::normalize;
 use serde::Deserialize;
 use serde::Serialize;
+use serde_json::Value;
 use std::collections::HashMap;
-use std::error::Error;
+use std::time::Duration;
 use time::macros::format_description;
 use time::PrimitiveDateTime;
 
+/// Fields of the QR code printed on a fiscal receipt which identify it.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TicketParams {
     #[serde(rename = "t")]
@@ -22,9 +26,30 @@ pub struct TicketParams {
 }
 
 impl TicketParams {
+    /// Unique key of the receipt, used as the ticket column of the database.
     pub fn key(&self) -> String {
         format!("{};{}", self.time, self.index)
     }
+
+    /// Total of the receipt in roubles.
+    pub fn sum(&self) -> f64 {
+        self.sum
+    }
+
+    /// Number of the fiscal drive which registered the receipt.
+    pub fn fiscal_storage(&self) -> u64 {
+        self.fiscal_storage
+    }
+
+    /// Number of the fiscal document.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Fiscal sign of the document.
+    pub fn fiscal_signature(&self) -> u64 {
+        self.fiscal_signature
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -36,6 +61,22 @@ struct OfdTicketResponse {
 struct OfdTicket {
     #[serde(rename = "transactionDate")]
     transaction_date: String,
+    #[serde(rename = "userInn", default)]
+    user_inn: Option<String>,
+    #[serde(default)]
+    user: Option<String>,
+    #[serde(rename = "retailPlaceAddress", default)]
+    retail_place_address: Option<String>,
+    #[serde(rename = "totalSum", default)]
+    total_sum: Option<u64>,
+    #[serde(rename = "cashTotalSum", default)]
+    cash_total_sum: Option<u64>,
+    #[serde(rename = "ecashTotalSum", default)]
+    ecash_total_sum: Option<u64>,
+    /// Registration number of the cash register, sent either as a string or
+    /// as a number.
+    #[serde(rename = "kktRegId", default)]
+    kkt_reg_id: Option<Value>,
     items: Vec<OfdTicketItem>,
 }
 
@@ -44,11 +85,51 @@ struct OfdTicketItem {
     name: String,
     quantity: f64,
     sum: u64,
+    #[serde(default)]
+    price: Option<u64>,
+    #[serde(default)]
+    unit: Option<String>,
+    #[serde(rename = "itemsQuantityMeasure", default)]
+    items_quantity_measure: Option<u32>,
 }
 
+impl OfdTicketItem {
+    /// Returns the factor converting quantity into base units together with
+    /// the base unit. FFD 1.2 uses measure codes, earlier versions use names.
+    fn measure(&self) -> Option<(f64, &'static str)> {
+        match self.items_quantity_measure {
+            Some(0) => return Some((1.0, "pcs")),
+            Some(10) => return Some((0.001, "kg")),
+            Some(11) => return Some((1.0, "kg")),
+            Some(40) => return Some((0.001, "l")),
+            Some(41) => return Some((1.0, "l")),
+            _ => {}
+        }
+
+        let unit = self.unit.as_ref()?.to_lowercase();
+
+        match unit.trim().trim_end_matches('.') {
+            "шт" | "pcs" => Some((1.0, "pcs")),
+            "г" | "гр" | "g" => Some((0.001, "kg")),
+            "кг" | "kg" => Some((1.0, "kg")),
+            "мл" | "ml" => Some((0.001, "l")),
+            "л" | "l" => Some((1.0, "l")),
+            _ => None,
+        }
+    }
+}
+
+/// Receipt loaded from the OFD. Items with the same name are merged.
 #[derive(Debug)]
 pub struct Ticket {
     datetime: PrimitiveDateTime,
+    inn: Option<String>,
+    seller: Option<String>,
+    address: Option<String>,
+    total: Option<f64>,
+    cash: Option<f64>,
+    card: Option<f64>,
+    register: Option<String>,
     items: Vec<TicketItem>,
 }
 
@@ -57,13 +138,45 @@ impl Ticket {
         &self.datetime
     }
 
+    pub fn inn(&self) -> Option<&str> {
+        self.inn.as_deref()
+    }
+
+    pub fn seller(&self) -> Option<&str> {
+        self.seller.as_deref()
+    }
+
+    pub fn address(&self) -> Option<&str> {
+        self.address.as_deref()
+    }
+
+    /// Total of the receipt in roubles.
+    pub fn total(&self) -> Option<f64> {
+        self.total
+    }
+
+    /// Part of the total paid in cash.
+    pub fn cash(&self) -> Option<f64> {
+        self.cash
+    }
+
+    /// Part of the total paid by card or other electronic means.
+    pub fn card(&self) -> Option<f64> {
+        self.card
+    }
+
+    /// Registration number of the cash register.
+    pub fn register(&self) -> Option<&str> {
+        self.register.as_deref()
+    }
+
     pub fn items(&self) -> &[TicketItem] {
         &self.items
     }
 }
 
 impl TryFrom<OfdTicketResponse> for Ticket {
-    type Error = Box<dyn Error>;
+    type Error = Error;
 
     fn try_from(value: OfdTicketResponse) -> Result<Self, Self::Error> {
         Self::try_from(value.ticket)
@@ -71,7 +184,7 @@ impl TryFrom<OfdTicketResponse> for Ticket {
 }
 
 impl TryFrom<OfdTicket> for Ticket {
-    type Error = Box<dyn Error>;
+    type Error = Error;
 
     fn try_from(value: OfdTicket) -> Result<Self, Self::Error> {
         let format = format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]");
@@ -84,20 +197,39 @@ impl TryFrom<OfdTicket> for Ticket {
                 .or_insert_with(|| TicketItem::new(&name));
             entry.quantity += item.quantity;
             entry.sum += 0.01 * item.sum as f64;
+            entry.price = item.price.map(|price| 0.01 * price as f64);
+
+            if let Some((factor, unit)) = item.measure() {
+                entry.measure = Some((factor, unit));
+            }
         }
 
         Ok(Ticket {
             datetime: PrimitiveDateTime::parse(&value.transaction_date, &format)?,
-            items: items.into_iter().map(|(_key, value)| value).collect(),
+            inn: value.user_inn.map(|inn| inn.trim().into()),
+            seller: value.user,
+            address: value.retail_place_address,
+            total: value.total_sum.map(|sum| 0.01 * sum as f64),
+            cash: value.cash_total_sum.map(|sum| 0.01 * sum as f64),
+            card: value.ecash_total_sum.map(|sum| 0.01 * sum as f64),
+            register: value.kkt_reg_id.and_then(|value| match value {
+                Value::String(value) => Some(value.trim().into()),
+                Value::Number(value) => Some(value.to_string()),
+                _ => None,
+            }),
+            items: items.into_values().collect(),
         })
     }
 }
 
+/// Receipt item with its quantity and sum in roubles.
 #[derive(Debug)]
 pub struct TicketItem {
     name: String,
     quantity: f64,
     sum: f64,
+    price: Option<f64>,
+    measure: Option<(f64, &'static str)>,
 }
 
 impl TicketItem {
@@ -106,6 +238,8 @@ impl TicketItem {
             name: name.into(),
             quantity: 0.0,
             sum: 0.0,
+            price: None,
+            measure: None,
         }
     }
 
@@ -120,11 +254,23 @@ impl TicketItem {
     pub fn sum(&self) -> f64 {
         self.sum
     }
+
+    pub fn price(&self) -> Option<f64> {
+        self.price
+    }
+
+    /// Returns bought amount in kilograms, litres or pieces. Items sold by
+    /// piece are converted using the pack size from the product name.
+    pub fn unit_quantity(&self) -> (f64, &'static str) {
+        unit_quantity(&self.name, self.quantity, self.measure)
+    }
 }
 
 impl From<OfdTicketItem> for TicketItem {
     fn from(value: OfdTicketItem) -> Self {
         TicketItem {
+            measure: value.measure(),
+            price: value.price.map(|price| 0.01 * price as f64),
             name: value.name,
             quantity: value.quantity,
             sum: 0.01 * value.sum as f64,
@@ -132,7 +278,28 @@ impl From<OfdTicketItem> for TicketItem {
     }
 }
 
-pub async fn load_params(data: &str) -> Result<TicketParams, Box<dyn Error>> {
+/// Converts a quantity of a product to kilograms, litres or pieces using the
+/// OFD measure when known and the pack size from the name otherwise.
+pub fn unit_quantity(
+    name: &str,
+    quantity: f64,
+    measure: Option<(f64, &'static str)>,
+) -> (f64, &'static str) {
+    match measure {
+        Some((factor, unit)) if unit != "pcs" => (quantity * factor, unit),
+        _ => {
+            let normalized = normalize(name);
+
+            match (normalized.amount(), normalized.unit()) {
+                (Some(amount), Some(unit)) => (quantity * amount, unit),
+                _ => (quantity, "pcs"),
+            }
+        }
+    }
+}
+
+/// Parses a QR code string like `t=20240101T1200&s=100.00&fn=...&i=...`.
+pub async fn load_params(data: &str) -> Result<TicketParams, Error> {
     let params: TicketParams = serde_qs::from_str(data)?;
 
     info!("OFD ticket params: {:?}", params);
@@ -140,23 +307,42 @@ pub async fn load_params(data: &str) -> Result<TicketParams, Box<dyn Error>> {
     Ok(params)
 }
 
-pub async fn load_ticket(params: &TicketParams) -> Result<Ticket, Box<dyn Error>> {
-    let uri = format!(
-        "https://consumer.1-ofd.ru/api/tickets/ticket/t={}&s={}&fn={}&i={}&fp={}&n={}",
-        params.time,
-        params.sum,
-        params.fiscal_storage,
-        params.index,
-        params.fiscal_signature,
-        params.number,
-    );
-    let body = reqwest::get(uri).await?.text().await?;
+/// OFD receipt API with its request settings.
+#[derive(Debug, Clone)]
+pub struct Provider {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl Provider {
+    pub fn new(url: &str, timeout: Duration) -> Result<Self, Error> {
+        let client = reqwest::Client::builder().timeout(timeout).build()?;
+
+        Ok(Self {
+            url: url.trim_end_matches('/').to_string(),
+            client,
+        })
+    }
+
+    pub async fn load_ticket(&self, params: &TicketParams) -> Result<Ticket, Error> {
+        let uri = format!(
+            "{}/t={}&s={}&fn={}&i={}&fp={}&n={}",
+            self.url,
+            params.time,
+            params.sum,
+            params.fiscal_storage,
+            params.index,
+            params.fiscal_signature,
+            params.number,
+        );
+        let body = self.client.get(uri).send().await?.text().await?;
 
-    info!("ODF response body: {:?}", body);
+        info!("ODF response body: {:?}", body);
 
-    let response: OfdTicketResponse = serde_json::from_str(&body)?;
+        let response: OfdTicketResponse = serde_json::from_str(&body)?;
 
-    info!("ODF response ticket: {:?}", response);
+        info!("ODF response ticket: {:?}", response);
 
-    Ok(response.try_into()?)
+        response.try_into()
+    }
 }
@@ -0,0 +1,154 @@
+use crate::api_error::ErrorReply;
+use crate::budgets;
+use crate::categories;
+use crate::households;
+use crate::products;
+use crate::qrcode;
+use crate::rules;
+use crate::splits;
+use crate::stores;
+use crate::tickets;
+use crate::tokens;
+use crate::users;
+use std::convert::Infallible;
+use utoipa::openapi::security::ApiKey;
+use utoipa::openapi::security::ApiKeyValue;
+use utoipa::openapi::security::Http;
+use utoipa::openapi::security::HttpAuthScheme;
+use utoipa::openapi::security::SecurityScheme;
+use utoipa::openapi::Content;
+use utoipa::openapi::Ref;
+use utoipa::openapi::RefOr;
+use utoipa::openapi::ResponseBuilder;
+use utoipa::Modify;
+use utoipa::OpenApi;
+
+/// OpenAPI document of the `/api/v1` routes generated from the handlers and
+/// their params and reply types.
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Receipt Check",
+        description = "Scanned receipts, product categories, budgets and splits of a household."
+    ),
+    paths(
+        users::register,
+        users::login,
+        users::logout,
+        users::me,
+        households::members,
+        households::create,
+        households::role,
+        households::delete,
+        tokens::list,
+        tokens::create,
+        tokens::delete,
+        qrcode::qrcode,
+        tickets::list,
+        tickets::receipt,
+        tickets::clear,
+        tickets::delete,
+        products::list,
+        products::inflation,
+        products::prices,
+        categories::update,
+        categories::assign,
+        stores::compare,
+        budgets::list,
+        budgets::update,
+        budgets::delete,
+        categories::list,
+        categories::tree,
+        categories::create,
+        categories::rename,
+        categories::move_node,
+        categories::merge,
+        categories::rename_path,
+        categories::merge_path,
+        categories::rename_name,
+        splits::people,
+        splits::create_person,
+        splits::delete_person,
+        splits::receipts,
+        splits::payer,
+        splits::share,
+        splits::balance,
+        rules::list,
+        rules::create,
+        rules::update,
+        rules::delete,
+        rules::preview,
+        rules::apply,
+    ),
+    components(schemas(ErrorReply)),
+    modifiers(&Errors),
+    security(("session" = []), ("token" = [])),
+    tags(
+        (name = "users", description = "Accounts and login sessions"),
+        (name = "household", description = "Members of the household, admins only"),
+        (name = "tokens", description = "API tokens for scripts"),
+        (name = "receipts", description = "Scanned receipts"),
+        (name = "products", description = "Products, prices and their categories"),
+        (name = "categories", description = "Category tree"),
+        (name = "budgets", description = "Monthly budgets of categories"),
+        (name = "splits", description = "Expenses shared between people"),
+        (name = "rules", description = "Categorization rules"),
+    )
+)]
+pub struct ApiDoc;
+
+/// Adds the security schemes and the error reply shared by all operations and
+/// prefixes operation ids with the tag, handlers of different modules share
+/// names like `list` or `delete`.
+struct Errors;
+
+impl Modify for Errors {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        let session = ApiKey::Cookie(ApiKeyValue::new(users::SESSION_COOKIE));
+        let error = ResponseBuilder::new()
+            .description("Error with a machine-readable code, see the `code` field")
+            .content(
+                "application/json",
+                Content::new(Some(Ref::from_schema_name("ErrorReply"))),
+            )
+            .build();
+
+        components.add_security_scheme("session", SecurityScheme::ApiKey(session));
+        components.add_security_scheme(
+            "token",
+            SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)),
+        );
+        components.responses.insert("Error".into(), RefOr::T(error));
+
+        for item in openapi.paths.paths.values_mut() {
+            let operations = [
+                &mut item.get,
+                &mut item.put,
+                &mut item.post,
+                &mut item.delete,
+            ];
+
+            for operation in operations.into_iter().flatten() {
+                let tag = operation.tags.iter().flatten().next();
+
+                if let (Some(tag), Some(id)) = (tag, &operation.operation_id) {
+                    operation.operation_id = Some(format!("{}_{}", tag, id));
+                }
+
+                for status in ["4XX", "5XX"] {
+                    operation
+                        .responses
+                        .responses
+                        .insert(status.into(), RefOr::Ref(Ref::from_response_name("Error")));
+                }
+            }
+        }
+    }
+}
+
+pub async fn openapi() -> Result<impl warp::Reply, Infallible> {
+    info!("Request OpenAPI document");
+
+    Ok(warp::reply::json(&ApiDoc::openapi()))
+}
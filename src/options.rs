@@ -1,45 +1,425 @@
+use serde::Deserialize;
+use std::env;
+use std::error::Error;
+use std::fs;
 use std::net::IpAddr;
+use std::net::Ipv4Addr;
+use std::net::Ipv6Addr;
 use std::path::Path;
 use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
 use structopt::StructOpt;
+use time::macros::format_description;
+use time::Date;
 
+const DEFAULT_ADDRESS: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+const DEFAULT_PORT: u16 = 8080;
+const DEFAULT_DATABASE: &str = "db.sqlite";
+const DEFAULT_OFD_URL: &str = "https://consumer.1-ofd.ru/api/tickets/ticket";
+const DEFAULT_OFD_TIMEOUT: u64 = 30;
+const DEFAULT_TRUSTED_PROXIES: [IpAddr; 2] = [
+    IpAddr::V4(Ipv4Addr::LOCALHOST),
+    IpAddr::V6(Ipv6Addr::LOCALHOST),
+];
+
+/// Options are taken from the command line first, then from `RECEIPT_CHECK_*`
+/// environment variables, then from the configuration file.
 #[derive(StructOpt, Debug)]
 #[structopt(name = "receipt-check")]
 pub struct Options {
-    #[structopt(short, long, default_value = "127.0.0.1")]
-    address: IpAddr,
+    /// TOML configuration file.
+    #[structopt(long, parse(from_os_str), env = "RECEIPT_CHECK_CONFIG")]
+    config: Option<PathBuf>,
+
+    /// Bind address [default: 127.0.0.1].
+    #[structopt(short, long, env = "RECEIPT_CHECK_ADDRESS")]
+    address: Option<IpAddr>,
+
+    /// Bind port [default: 8080].
+    #[structopt(short, long, env = "RECEIPT_CHECK_PORT")]
+    port: Option<u16>,
+
+    /// Database path [default: db.sqlite].
+    #[structopt(short, long, parse(from_os_str), env = "RECEIPT_CHECK_DATABASE")]
+    database: Option<PathBuf>,
+
+    /// Directory with web interface files used instead of the embedded ones.
+    #[structopt(long, parse(from_os_str), env = "RECEIPT_CHECK_PUBLIC_DIR")]
+    public_dir: Option<PathBuf>,
+
+    #[structopt(short, long, parse(from_os_str), env = "RECEIPT_CHECK_KEY")]
+    key: Option<PathBuf>,
+
+    #[structopt(short, long, parse(from_os_str), env = "RECEIPT_CHECK_CERTIFICATE")]
+    certificate: Option<PathBuf>,
+
+    /// Serve plain HTTP behind a reverse proxy terminating TLS. Client address
+    /// and protocol are taken from `X-Forwarded-For` and `X-Forwarded-Proto`
+    /// of trusted proxies.
+    #[structopt(long, conflicts_with_all = &["key", "certificate"])]
+    no_tls: bool,
+
+    /// Address of a reverse proxy allowed to set the forwarded headers
+    /// without TLS, can be repeated [default: 127.0.0.1, ::1].
+    #[structopt(
+        long = "trusted-proxy",
+        env = "RECEIPT_CHECK_TRUSTED_PROXY",
+        use_delimiter = true
+    )]
+    trusted_proxies: Vec<IpAddr>,
+
+    /// URL of the OFD receipt API.
+    #[structopt(long, env = "RECEIPT_CHECK_OFD_URL")]
+    ofd_url: Option<String>,
+
+    /// Timeout of OFD requests in seconds [default: 30].
+    #[structopt(long, env = "RECEIPT_CHECK_OFD_TIMEOUT")]
+    ofd_timeout: Option<u64>,
+
+    /// User name required by HTTP Basic authentication.
+    #[structopt(long, env = "RECEIPT_CHECK_BASIC_USER")]
+    basic_user: Option<String>,
+
+    /// Argon2 password hash of the HTTP Basic authentication user.
+    #[structopt(long, env = "RECEIPT_CHECK_BASIC_PASSWORD_HASH")]
+    basic_password_hash: Option<String>,
+
+    /// File with `user:hash` lines of HTTP Basic authentication users.
+    #[structopt(long, parse(from_os_str), env = "RECEIPT_CHECK_BASIC_AUTH_FILE")]
+    basic_auth_file: Option<PathBuf>,
+
+    /// Log filter in `env_logger` format, e.g. `info` or `receipt_check=debug`.
+    #[structopt(long, env = "RECEIPT_CHECK_LOG")]
+    log: Option<String>,
+
+    #[structopt(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(StructOpt, Debug)]
+pub enum Command {
+    /// Starts the web service, the default command.
+    Serve,
+
+    /// Configuration commands.
+    Config(ConfigCommand),
+
+    /// Loads the receipt of a QR code string from the OFD and saves it.
+    Fetch {
+        /// Household receiving the receipt.
+        #[structopt(long, default_value = "1")]
+        household: i64,
+
+        /// QR code string, e.g. `t=20240101T1200&s=100.00&fn=...&i=...&fp=...&n=1`.
+        data: String,
+    },
+
+    /// Loads receipts of QR code strings listed one per line in a file.
+    Import {
+        /// Household receiving the receipts.
+        #[structopt(long, default_value = "1")]
+        household: i64,
+
+        /// File with QR code strings, `-` reads the standard input.
+        #[structopt(parse(from_os_str))]
+        file: PathBuf,
+    },
+
+    /// Exports receipt items.
+    Export {
+        /// Household of the exported receipts.
+        #[structopt(long, default_value = "1")]
+        household: i64,
+
+        /// Output format: `csv` or `json`.
+        #[structopt(short, long, default_value = "csv")]
+        format: ExportFormat,
+
+        /// First date of exported receipts, e.g. `2026-01-01`.
+        #[structopt(long, parse(try_from_str = parse_date))]
+        from: Option<Date>,
+
+        /// Last date of exported receipts, e.g. `2026-12-31`.
+        #[structopt(long, parse(try_from_str = parse_date))]
+        to: Option<Date>,
+
+        /// Output file, the standard output by default.
+        #[structopt(short, long, parse(from_os_str))]
+        output: Option<PathBuf>,
+    },
+
+    /// Prints a report: `monthly` spending, price `inflation` or `budgets`.
+    Report {
+        /// Household of the report.
+        #[structopt(long, default_value = "1")]
+        household: i64,
+
+        report: ReportKind,
+    },
+
+    /// Assigns receipts, categories and rules stored before accounts were
+    /// introduced to a household without data.
+    Assign {
+        /// Household receiving the data.
+        #[structopt(long)]
+        household: i64,
+    },
+
+    /// Links products to canonical products and inherits their categories.
+    Categorize {
+        /// Household of the categorized products.
+        #[structopt(long, default_value = "1")]
+        household: i64,
+
+        /// Applies categorization rules to products without a category.
+        #[structopt(long)]
+        apply_rules: bool,
+
+        /// Lets rules replace categories assigned before.
+        #[structopt(long, requires = "apply-rules")]
+        overwrite: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
+            _ => Err(format!("Unknown export format: {}", value)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ReportKind {
+    Monthly,
+    Inflation,
+    Budgets,
+}
+
+impl FromStr for ReportKind {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "monthly" => Ok(Self::Monthly),
+            "inflation" => Ok(Self::Inflation),
+            "budgets" => Ok(Self::Budgets),
+            _ => Err(format!("Unknown report: {}", value)),
+        }
+    }
+}
+
+#[derive(StructOpt, Debug)]
+pub enum ConfigCommand {
+    /// Validates the configuration and prints the effective options.
+    Check,
+}
 
-    #[structopt(short, long, default_value = "8080")]
-    port: u16,
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+struct Config {
+    address: Option<IpAddr>,
+    port: Option<u16>,
+    database: Option<PathBuf>,
+    public_dir: Option<PathBuf>,
+    tls: TlsConfig,
+    ofd: OfdConfig,
+    auth: AuthConfig,
+    log: Option<String>,
+}
 
-    #[structopt(short, long, parse(from_os_str), default_value = "db.sqlite")]
-    database: PathBuf,
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+struct TlsConfig {
+    enabled: Option<bool>,
+    key: Option<PathBuf>,
+    certificate: Option<PathBuf>,
+    trusted_proxies: Option<Vec<IpAddr>>,
+}
 
-    #[structopt(short, long, parse(from_os_str))]
-    key: PathBuf,
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+struct OfdConfig {
+    url: Option<String>,
+    timeout: Option<u64>,
+}
 
-    #[structopt(short, long, parse(from_os_str))]
-    certificate: PathBuf,
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+struct AuthConfig {
+    basic_user: Option<String>,
+    basic_password_hash: Option<String>,
+    basic_auth_file: Option<PathBuf>,
 }
 
 impl Options {
+    /// Parses the command line and completes it with the environment and the
+    /// configuration file.
+    pub fn load() -> Result<Self, Box<dyn Error>> {
+        let mut options = Self::from_args();
+
+        if !options.no_tls {
+            options.no_tls = env_flag("RECEIPT_CHECK_NO_TLS")?.unwrap_or(false);
+        }
+
+        if let Some(path) = &options.config {
+            let content = fs::read_to_string(path)
+                .map_err(|error| format!("Failed to read {}: {}", path.display(), error))?;
+            let config: Config = toml::from_str(&content)
+                .map_err(|error| format!("Invalid config {}: {}", path.display(), error))?;
+
+            options.merge(config);
+        }
+
+        options.validate()?;
+
+        Ok(options)
+    }
+
+    fn merge(&mut self, config: Config) {
+        let no_tls_set = self.no_tls || env::var_os("RECEIPT_CHECK_NO_TLS").is_some();
+
+        self.address = self.address.or(config.address);
+        self.port = self.port.or(config.port);
+        self.database = self.database.take().or(config.database);
+        self.public_dir = self.public_dir.take().or(config.public_dir);
+        self.key = self.key.take().or(config.tls.key);
+        self.certificate = self.certificate.take().or(config.tls.certificate);
+
+        if self.trusted_proxies.is_empty() {
+            self.trusted_proxies = config.tls.trusted_proxies.unwrap_or_default();
+        }
+
+        self.ofd_url = self.ofd_url.take().or(config.ofd.url);
+        self.ofd_timeout = self.ofd_timeout.or(config.ofd.timeout);
+        self.basic_user = self.basic_user.take().or(config.auth.basic_user);
+        self.basic_password_hash = self
+            .basic_password_hash
+            .take()
+            .or(config.auth.basic_password_hash);
+        self.basic_auth_file = self.basic_auth_file.take().or(config.auth.basic_auth_file);
+        self.log = self.log.take().or(config.log);
+
+        if !no_tls_set {
+            self.no_tls = config.tls.enabled == Some(false);
+        }
+    }
+
+    fn validate(&self) -> Result<(), Box<dyn Error>> {
+        let serve = matches!(
+            self.command,
+            None | Some(Command::Serve) | Some(Command::Config(_))
+        );
+
+        if serve && self.tls() && (self.key.is_none() || self.certificate.is_none()) {
+            return Err("TLS key and certificate are required unless TLS is disabled".into());
+        }
+
+        if self.basic_user.is_some() != self.basic_password_hash.is_some() {
+            return Err("Basic user and basic password hash must be set together".into());
+        }
+
+        if self.ofd_timeout == Some(0) {
+            return Err("OFD timeout must be positive".into());
+        }
+
+        Ok(())
+    }
+
+    pub fn command(&self) -> Option<&Command> {
+        self.command.as_ref()
+    }
+
+    pub fn config(&self) -> Option<&Path> {
+        self.config.as_deref()
+    }
+
     pub fn address(&self) -> &IpAddr {
-        &self.address
+        self.address.as_ref().unwrap_or(&DEFAULT_ADDRESS)
     }
 
     pub fn port(&self) -> u16 {
-        self.port
+        self.port.unwrap_or(DEFAULT_PORT)
     }
 
     pub fn database(&self) -> &Path {
-        &self.database
+        self.database
+            .as_deref()
+            .unwrap_or_else(|| Path::new(DEFAULT_DATABASE))
+    }
+
+    pub fn public_dir(&self) -> Option<&Path> {
+        self.public_dir.as_deref()
     }
 
-    pub fn key(&self) -> &Path {
-        &self.key
+    pub fn key(&self) -> Option<&Path> {
+        self.key.as_deref()
     }
 
-    pub fn certificate(&self) -> &Path {
-        &self.certificate
+    pub fn certificate(&self) -> Option<&Path> {
+        self.certificate.as_deref()
+    }
+
+    pub fn tls(&self) -> bool {
+        !self.no_tls
+    }
+
+    pub fn trusted_proxies(&self) -> &[IpAddr] {
+        if self.trusted_proxies.is_empty() {
+            &DEFAULT_TRUSTED_PROXIES
+        } else {
+            &self.trusted_proxies
+        }
+    }
+
+    pub fn ofd_url(&self) -> &str {
+        self.ofd_url.as_deref().unwrap_or(DEFAULT_OFD_URL)
+    }
+
+    pub fn ofd_timeout(&self) -> Duration {
+        Duration::from_secs(self.ofd_timeout.unwrap_or(DEFAULT_OFD_TIMEOUT))
+    }
+
+    pub fn basic_user(&self) -> Option<&str> {
+        self.basic_user.as_deref()
+    }
+
+    pub fn basic_password_hash(&self) -> Option<&str> {
+        self.basic_password_hash.as_deref()
+    }
+
+    pub fn basic_auth_file(&self) -> Option<&Path> {
+        self.basic_auth_file.as_deref()
+    }
+
+    pub fn log(&self) -> Option<&str> {
+        self.log.as_deref()
+    }
+}
+
+fn parse_date(value: &str) -> Result<Date, time::error::Parse> {
+    Date::parse(value, format_description!("[year]-[month]-[day]"))
+}
+
+fn env_flag(name: &str) -> Result<Option<bool>, Box<dyn Error>> {
+    match env::var(name) {
+        Ok(value) => match value.to_lowercase().as_str() {
+            "1" | "true" | "yes" | "on" => Ok(Some(true)),
+            "0" | "false" | "no" | "off" | "" => Ok(Some(false)),
+            _ => Err(format!("Invalid {} value: {}", name, value).into()),
+        },
+        Err(env::VarError::NotPresent) => Ok(None),
+        Err(error) => Err(format!("Invalid {} value: {}", name, error).into()),
     }
 }
@@ -0,0 +1,149 @@
+use super::inflation::InflationMonth;
+use crate::database::CanonicalProductData;
+use crate::database::PriceData;
+use crate::database::ProductData;
+use serde::Deserialize;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = products::ReplyProduct)]
+pub struct ReplyProduct {
+    product: String,
+    category: String,
+    name: String,
+}
+
+impl From<&ProductData> for ReplyProduct {
+    fn from(value: &ProductData) -> Self {
+        Self {
+            product: value.product().into(),
+            category: value.category().cloned().unwrap_or_default(),
+            name: value.name().cloned().unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = products::ReplyItem)]
+pub struct ReplyItem {
+    id: i64,
+    title: String,
+    amount: Option<f64>,
+    unit: Option<String>,
+    fat: Option<f64>,
+    products: Vec<ReplyProduct>,
+}
+
+impl From<CanonicalProductData> for ReplyItem {
+    fn from(value: CanonicalProductData) -> Self {
+        Self {
+            id: value.id(),
+            title: value.title().into(),
+            amount: value.amount(),
+            unit: value.unit().cloned(),
+            fat: value.fat(),
+            products: value.products().iter().map(ReplyProduct::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = products::ReplyPrice)]
+pub struct ReplyPrice {
+    date: String,
+    product: String,
+    store: String,
+    inn: Option<String>,
+    unit: Option<String>,
+    sum: f64,
+    unit_price: Option<f64>,
+}
+
+impl From<PriceData> for ReplyPrice {
+    fn from(value: PriceData) -> Self {
+        let store = value
+            .seller()
+            .or(value.address())
+            .or(value.inn())
+            .cloned()
+            .unwrap_or_default();
+
+        Self {
+            date: value.date().into(),
+            product: value.product().into(),
+            store,
+            inn: value.inn().cloned(),
+            unit: value.unit().cloned(),
+            sum: value.sum(),
+            unit_price: value.unit_price(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = products::ReplyMonth)]
+pub struct ReplyMonth {
+    month: String,
+    index: f64,
+    cumulative: f64,
+    products: usize,
+}
+
+impl From<InflationMonth> for ReplyMonth {
+    fn from(value: InflationMonth) -> Self {
+        Self {
+            month: value.month().into(),
+            index: value.index(),
+            cumulative: value.cumulative(),
+            products: value.products(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(untagged)]
+#[schema(as = products::Reply)]
+pub enum Reply {
+    List {
+        success: bool,
+        items: Vec<ReplyItem>,
+    },
+    Prices {
+        success: bool,
+        prices: Vec<ReplyPrice>,
+    },
+    Inflation {
+        success: bool,
+        months: Vec<ReplyMonth>,
+    },
+}
+
+impl Reply {
+    pub fn list(items: Vec<CanonicalProductData>) -> Self {
+        let items = items.into_iter().map(ReplyItem::from).collect();
+
+        Reply::List {
+            success: true,
+            items,
+        }
+    }
+
+    pub fn prices(prices: Vec<PriceData>) -> Self {
+        let prices = prices.into_iter().map(ReplyPrice::from).collect();
+
+        Reply::Prices {
+            success: true,
+            prices,
+        }
+    }
+
+    pub fn inflation(months: Vec<InflationMonth>) -> Self {
+        let months = months.into_iter().map(ReplyMonth::from).collect();
+
+        Reply::Inflation {
+            success: true,
+            months,
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use crate::database::MonthlyPriceData;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+
+/// Canonical products bought in fewer months are not part of the basket.
+const BASKET_MIN_MONTHS: usize = 2;
+
+/// Maximal number of most frequently bought products in the basket.
+const BASKET_SIZE: usize = 50;
+
+#[derive(Debug)]
+pub struct InflationMonth {
+    month: String,
+    index: f64,
+    cumulative: f64,
+    products: usize,
+}
+
+impl InflationMonth {
+    pub fn month(&self) -> &str {
+        &self.month
+    }
+
+    pub fn index(&self) -> f64 {
+        self.index
+    }
+
+    pub fn cumulative(&self) -> f64 {
+        self.cumulative
+    }
+
+    pub fn products(&self) -> usize {
+        self.products
+    }
+}
+
+/// Canonical product with the unit of its prices, a product sold both by
+/// weight and by piece is two basket entries.
+type BasketKey<'a> = (i64, &'a str);
+
+/// Computes a chained month over month price index. Each month index is the
+/// geometric mean of unit price ratios of basket products bought in both the
+/// month and the previous one in the same unit.
+pub fn inflation(prices: Vec<MonthlyPriceData>) -> Vec<InflationMonth> {
+    let mut months: HashMap<BasketKey, usize> = HashMap::new();
+    let mut purchases: HashMap<BasketKey, usize> = HashMap::new();
+
+    for price in &prices {
+        let key = (price.canonical(), price.unit());
+
+        *months.entry(key).or_default() += 1;
+        *purchases.entry(key).or_default() += price.purchases();
+    }
+
+    let mut basket: Vec<BasketKey> = months
+        .iter()
+        .filter(|(_, months)| **months >= BASKET_MIN_MONTHS)
+        .map(|(key, _)| *key)
+        .collect();
+    basket.sort_by_key(|key| (std::cmp::Reverse(purchases[key]), *key));
+    basket.truncate(BASKET_SIZE);
+
+    let mut table: BTreeMap<String, HashMap<BasketKey, f64>> = BTreeMap::new();
+
+    for price in &prices {
+        let month = table.entry(price.month().into()).or_default();
+        let key = (price.canonical(), price.unit());
+
+        if basket.contains(&key) {
+            month.insert(key, price.unit_price());
+        }
+    }
+
+    let mut result = Vec::with_capacity(table.len());
+    let mut previous: Option<&HashMap<BasketKey, f64>> = None;
+    let mut cumulative = 1.0;
+
+    for (month, current) in &table {
+        let ratios: Vec<f64> = match previous {
+            Some(previous) => current
+                .iter()
+                .filter_map(|(key, price)| {
+                    previous
+                        .get(key)
+                        .filter(|previous| **previous > 0.0)
+                        .map(|previous| price / previous)
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        let index = match ratios.len() {
+            0 => 1.0,
+            count => (ratios.iter().map(|ratio| ratio.ln()).sum::<f64>() / count as f64).exp(),
+        };
+        cumulative *= index;
+
+        result.push(InflationMonth {
+            month: month.clone(),
+            index,
+            cumulative,
+            products: ratios.len(),
+        });
+        previous = Some(current);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn price(canonical: i64, unit: &str, month: &str, sum: f64) -> MonthlyPriceData {
+        MonthlyPriceData::new(canonical, unit.into(), month.into(), sum, 1.0, 1)
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn flat_prices_give_unit_index() {
+        let prices = vec![
+            price(1, "l", "2026.01", 90.0),
+            price(2, "kg", "2026.01", 60.0),
+            price(1, "l", "2026.02", 90.0),
+            price(2, "kg", "2026.02", 60.0),
+            price(1, "l", "2026.03", 90.0),
+            price(2, "kg", "2026.03", 60.0),
+        ];
+        let months = inflation(prices);
+
+        assert_eq!(months.len(), 3);
+        assert_eq!(months[0].products(), 0);
+
+        for month in &months {
+            assert_close(month.index(), 1.0);
+            assert_close(month.cumulative(), 1.0);
+        }
+
+        assert_eq!(months[2].products(), 2);
+    }
+
+    #[test]
+    fn single_doubling() {
+        let prices = vec![
+            price(1, "l", "2026.01", 90.0),
+            price(1, "l", "2026.02", 180.0),
+            price(1, "l", "2026.03", 180.0),
+        ];
+        let months = inflation(prices);
+
+        assert_close(months[1].index(), 2.0);
+        assert_close(months[1].cumulative(), 2.0);
+        assert_close(months[2].index(), 1.0);
+        assert_close(months[2].cumulative(), 2.0);
+    }
+
+    #[test]
+    fn geometric_mean_of_ratios() {
+        let prices = vec![
+            price(1, "l", "2026.01", 100.0),
+            price(2, "kg", "2026.01", 100.0),
+            price(1, "l", "2026.02", 200.0),
+            price(2, "kg", "2026.02", 50.0),
+        ];
+        let months = inflation(prices);
+
+        assert_close(months[1].index(), 1.0);
+        assert_eq!(months[1].products(), 2);
+    }
+
+    #[test]
+    fn prices_in_different_units_are_not_compared() {
+        let prices = vec![
+            price(1, "kg", "2026.01", 200.0),
+            price(1, "pcs", "2026.02", 50.0),
+        ];
+        let months = inflation(prices);
+
+        assert_eq!(months[1].products(), 0);
+        assert_close(months[1].index(), 1.0);
+    }
+}
@@ -0,0 +1,136 @@
+mod data;
+mod inflation;
+
+pub use self::data::Reply;
+pub use self::inflation::InflationMonth;
+
+use crate::api_error::OrReject;
+use crate::database::Database;
+use crate::database::UserData;
+use crate::normalizer::normalize;
+use crate::ofd::unit_quantity;
+use std::error::Error;
+use warp::Rejection;
+
+/// Lists canonical products with their receipt products.
+#[utoipa::path(
+    get,
+    path = "/api/v1/products",
+    tag = "products",
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn list(user: UserData, database: Database) -> Result<impl warp::Reply, Rejection> {
+    info!("Request canonical products");
+
+    let items = database
+        .select_canonical_products(user.owner())
+        .await
+        .or_reject("Failed to read products")?;
+
+    Ok(warp::reply::json(&Reply::list(items)))
+}
+
+/// Lists prices of a canonical product.
+#[utoipa::path(
+    get,
+    path = "/api/v1/products/{id}/prices",
+    tag = "products",
+    params(
+        ("id" = i64, Path, description = "Canonical product id"),
+    ),
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn prices(
+    id: i64,
+    user: UserData,
+    database: Database,
+) -> Result<impl warp::Reply, Rejection> {
+    info!("Request product prices: {}", id);
+
+    let prices = database
+        .select_canonical_prices(user.owner(), id)
+        .await
+        .or_reject("Failed to read prices")?;
+
+    Ok(warp::reply::json(&Reply::prices(prices)))
+}
+
+/// Returns the monthly price index.
+#[utoipa::path(
+    get,
+    path = "/api/v1/products/inflation",
+    tag = "products",
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn inflation(user: UserData, database: Database) -> Result<impl warp::Reply, Rejection> {
+    info!("Request inflation");
+
+    let prices = database
+        .select_monthly_prices(user.owner())
+        .await
+        .or_reject("Failed to read prices")?;
+
+    let months = inflation::inflation(prices);
+
+    Ok(warp::reply::json(&Reply::inflation(months)))
+}
+
+/// Computes the monthly price index of the owner's products.
+pub async fn monthly_inflation(
+    database: &Database,
+    owner: i64,
+) -> Result<Vec<InflationMonth>, Box<dyn Error>> {
+    let prices = database.select_monthly_prices(owner).await?;
+
+    Ok(inflation::inflation(prices))
+}
+
+/// Links raw product names to their canonical products.
+pub async fn canonicalize<S>(database: &Database, products: &[S]) -> Result<(), Box<dyn Error>>
+where
+    S: AsRef<str>,
+{
+    for product in products {
+        let product = product.as_ref();
+        let normalized = normalize(product);
+
+        database
+            .insert_product_canonical(
+                product,
+                normalized.key(),
+                normalized.title(),
+                normalized.amount(),
+                normalized.unit(),
+                normalized.fat(),
+            )
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Gives products of the household without a category the most common
+/// category of other products with the same canonical product.
+pub async fn inherit_categories(database: &Database, owner: i64) -> Result<(), Box<dyn Error>> {
+    let count = database.inherit_canonical_categories(owner).await?;
+
+    if count > 0 {
+        info!("Inherited categories for {} products", count);
+    }
+
+    Ok(())
+}
+
+/// Fills measurement units of ticket items stored before units were tracked,
+/// the pack size is taken from the product name.
+pub async fn measure(database: &Database) -> Result<(), Box<dyn Error>> {
+    for (id, product, quantity) in database.select_unmeasured_ticket_items().await? {
+        let (unit_quantity, unit) = unit_quantity(&product, quantity, None);
+
+        database
+            .update_ticket_item_unit(id, unit, unit_quantity)
+            .await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,189 @@
+use crate::options::Options;
+use std::convert::Infallible;
+use std::net::IpAddr;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use warp::http::HeaderMap;
+use warp::http::Method;
+use warp::http::StatusCode;
+use warp::http::Uri;
+use warp::http::Version;
+use warp::Filter;
+
+/// Reverse proxies trusted to set the forwarded headers. The headers are
+/// ignored with TLS, where clients connect directly, and from other peers,
+/// so clients cannot spoof their address or protocol.
+#[derive(Debug, Clone)]
+pub struct Proxies {
+    tls: bool,
+    trusted: Arc<Vec<IpAddr>>,
+}
+
+impl Proxies {
+    pub fn new(options: &Options) -> Self {
+        Self {
+            tls: options.tls(),
+            trusted: Arc::new(options.trusted_proxies().to_vec()),
+        }
+    }
+
+    fn is_trusted(&self, address: IpAddr) -> bool {
+        !self.tls && self.trusted.contains(&address)
+    }
+}
+
+/// Client address and protocol of a request. Without TLS the server is
+/// expected to run behind a reverse proxy, so both are taken from the
+/// `X-Forwarded-For` and `X-Forwarded-Proto` headers set by a trusted proxy.
+/// The protocol only decides the `Secure` flag of the session cookie and the
+/// access log, the server sends no redirects.
+#[derive(Debug, Clone)]
+pub struct Forwarded {
+    address: Option<String>,
+    secure: bool,
+}
+
+impl Forwarded {
+    pub fn new(proxies: &Proxies, remote: Option<SocketAddr>, headers: &HeaderMap) -> Self {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.trim())
+                .filter(|value| !value.is_empty())
+        };
+        let remote_ip = remote.map(|remote| remote.ip());
+        let remote = remote_ip.map(|remote| remote.to_string());
+
+        if proxies.tls {
+            return Self {
+                address: remote,
+                secure: true,
+            };
+        }
+
+        if !remote_ip.is_some_and(|remote| proxies.is_trusted(remote)) {
+            return Self {
+                address: remote,
+                secure: false,
+            };
+        }
+
+        // Proxies append the peer address, so the client is the last address
+        // not added by another trusted proxy.
+        let address = header("x-forwarded-for")
+            .and_then(|value| {
+                value.rsplit(',').map(|value| value.trim()).find(|value| {
+                    value
+                        .parse()
+                        .map_or(true, |address| !proxies.is_trusted(address))
+                })
+            })
+            .map(|value| value.to_string())
+            .or(remote);
+        let secure = header("x-forwarded-proto")
+            .map(|value| value.eq_ignore_ascii_case("https"))
+            .unwrap_or(false);
+
+        Self { address, secure }
+    }
+
+    pub fn address(&self) -> &str {
+        self.address.as_deref().unwrap_or("-")
+    }
+
+    pub fn is_secure(&self) -> bool {
+        self.secure
+    }
+
+    pub fn scheme(&self) -> &'static str {
+        if self.secure {
+            "https"
+        } else {
+            "http"
+        }
+    }
+}
+
+/// Address of the connected peer, attached to every request by the server.
+#[derive(Debug, Clone, Copy)]
+pub struct RemoteAddress(pub SocketAddr);
+
+pub fn forwarded(
+    proxies: Proxies,
+) -> impl Filter<Extract = (Forwarded,), Error = Infallible> + Clone {
+    warp::ext::optional::<RemoteAddress>()
+        .and(warp::header::headers_cloned())
+        .map(move |remote: Option<RemoteAddress>, headers: HeaderMap| {
+            Forwarded::new(&proxies, remote.map(|remote| remote.0), &headers)
+        })
+}
+
+/// Writes an access log line with the client address seen by the proxy.
+pub fn log(
+    forwarded: &Forwarded,
+    method: &Method,
+    uri: &Uri,
+    version: Version,
+    status: StatusCode,
+    elapsed: Duration,
+) {
+    info!(
+        "{} {} \"{} {} {:?}\" {} {:?}",
+        forwarded.address(),
+        forwarded.scheme(),
+        method,
+        uri.path(),
+        version,
+        status.as_u16(),
+        elapsed,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    fn proxies(tls: bool) -> Proxies {
+        Proxies {
+            tls,
+            trusted: Arc::new(vec![IpAddr::V4(Ipv4Addr::LOCALHOST)]),
+        }
+    }
+
+    fn headers() -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", "203.0.113.7, 127.0.0.1".parse().unwrap());
+        headers.insert("x-forwarded-proto", "https".parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn trusted_proxy_headers_are_honored() {
+        let remote = "127.0.0.1:40000".parse().ok();
+        let forwarded = Forwarded::new(&proxies(false), remote, &headers());
+
+        assert_eq!(forwarded.address(), "203.0.113.7");
+        assert!(forwarded.is_secure());
+    }
+
+    #[test]
+    fn untrusted_peer_headers_are_ignored() {
+        let remote = "198.51.100.2:40000".parse().ok();
+        let forwarded = Forwarded::new(&proxies(false), remote, &headers());
+
+        assert_eq!(forwarded.address(), "198.51.100.2");
+        assert!(!forwarded.is_secure());
+    }
+
+    #[test]
+    fn tls_headers_are_ignored() {
+        let remote = "127.0.0.1:40000".parse().ok();
+        let forwarded = Forwarded::new(&proxies(true), remote, &headers());
+
+        assert_eq!(forwarded.address(), "127.0.0.1");
+        assert!(forwarded.is_secure());
+    }
+}
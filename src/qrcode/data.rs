@@ -1,24 +1,27 @@
 use serde::Deserialize;
 use serde::Serialize;
+use utoipa::ToSchema;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = qrcode::Reply)]
 pub struct Reply {
     success: bool,
-    message: Option<String>,
+    #[serde(default)]
+    warnings: Vec<String>,
 }
 
 impl Reply {
     pub fn success() -> Self {
         Reply {
             success: true,
-            message: None,
+            warnings: Vec::new(),
         }
     }
 
-    pub fn error(message: &str) -> Self {
+    pub fn warnings(warnings: Vec<String>) -> Self {
         Reply {
-            success: false,
-            message: Some(message.into()),
+            success: true,
+            warnings,
         }
     }
 }
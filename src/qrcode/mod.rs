@@ -2,55 +2,143 @@ mod data;
 
 pub use self::data::Reply;
 
+use crate::api_error::OrReject;
+use crate::budgets;
 use crate::database::Database;
+use crate::database::ReceiptData;
+use crate::database::UserData;
 use crate::ofd::load_params;
-use crate::ofd::load_ticket;
-use std::convert::Infallible;
+use crate::ofd::Provider;
+use crate::products;
+use crate::rules;
+use crate::users;
+use std::error::Error;
 use time::macros::format_description;
+use warp::Rejection;
 
-macro_rules! no_fail {
-    ($message:expr, $callback:expr) => {
-        match $callback {
-            Ok(result) => result,
-            Err(error) => {
-                warn!("{}: {}", $message, error);
-
-                let message = format!("{}", error);
+/// Loads the receipt of a QR code string from the OFD and saves it.
+#[utoipa::path(
+    post,
+    path = "/api/v1/receipts",
+    tag = "receipts",
+    request_body = String,
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn qrcode(
+    user: UserData,
+    data: String,
+    database: Database,
+    provider: Provider,
+) -> Result<impl warp::Reply, Rejection> {
+    info!("Request data: {}", data);
 
-                return Ok(warp::reply::json(&Reply::error(&message)));
-            }
-        }
+    let warnings = scan(&database, &provider, user.owner(), Some(user.id()), &data)
+        .await
+        .or_reject("Failed to scan receipt")?;
+    let reply = match warnings {
+        Some(warnings) => Reply::warnings(warnings),
+        None => Reply::success(),
     };
-}
 
-pub async fn qrcode(data: String, database: Database) -> Result<impl warp::Reply, Infallible> {
-    info!("Request data: {}", data);
+    Ok(warp::reply::json(&reply))
+}
 
-    let params = no_fail!("Failed to load ticket", load_params(&data).await);
+/// Loads the receipt of a QR code from the OFD and saves it with categorized
+/// products. Returns budget overspend warnings, or `None` when the receipt
+/// was already saved. Categorization failures after saving are only logged.
+pub async fn scan(
+    database: &Database,
+    provider: &Provider,
+    owner: i64,
+    user: Option<i64>,
+    data: &str,
+) -> Result<Option<Vec<String>>, Box<dyn Error>> {
+    let params = load_params(data).await?;
     let key = params.key();
-    let count = no_fail!(
-        "Failed to count items",
-        database.ticket_item_count(&key).await
-    );
 
-    if count > 0 {
+    if database.ticket_item_count(owner, &key).await? > 0 {
         info!("Ticket already exists.");
 
-        return Ok(warp::reply::json(&Reply::success()));
+        return Ok(None);
     }
 
-    let ticket = no_fail!("Failed to load ticket", load_ticket(&params).await);
+    let ticket = provider.load_ticket(&params).await?;
     let format = format_description!("[year].[month].[day]");
-    let date_string = no_fail!("Failed to format date", ticket.datetime().format(&format));
+    let date_string = ticket.datetime().format(&format)?;
+    let format = format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]");
+    let time_string = ticket.datetime().format(&format)?;
+
+    let month = budgets::current_month()?;
+    let budgets_before = database.select_budgets(owner, &month).await?;
+
+    let receipt = ReceiptData::new(key.clone(), Some(time_string))
+        .with_store(
+            ticket.inn().map(String::from),
+            ticket.seller().map(String::from),
+            ticket.address().map(String::from),
+        )
+        .with_payment(
+            ticket.total().or(Some(params.sum())),
+            ticket.cash(),
+            ticket.card(),
+        )
+        .with_fiscal(
+            Some(params.fiscal_storage().to_string()),
+            Some(params.index().to_string()),
+            Some(params.fiscal_signature().to_string()),
+            ticket.register().map(String::from),
+        )
+        .with_scan(Some(users::now()), None);
+
+    database.insert_receipt(owner, user, &receipt).await?;
 
     for item in ticket.items() {
-        no_fail!(
-            "Failed to save product",
-            database
-                .insert_ticket_item(&key, &date_string, item.name(), item.quantity(), item.sum())
-                .await
-        );
+        let (unit_quantity, unit) = item.unit_quantity();
+
+        database
+            .insert_ticket_item(
+                owner,
+                &key,
+                &date_string,
+                item.name(),
+                item.quantity(),
+                item.sum(),
+                item.price(),
+                unit,
+                unit_quantity,
+            )
+            .await?;
+    }
+
+    let products: Vec<_> = ticket.items().iter().map(|item| item.name()).collect();
+
+    // The receipt is saved at this point, so failures of the following steps
+    // are logged and do not fail the scan, otherwise a rescan would report
+    // the receipt as a duplicate.
+    if let Err(error) = products::canonicalize(database, &products).await {
+        warn!("Failed to canonicalize products: {}", error);
+    }
+
+    if let Err(error) = products::inherit_categories(database, owner).await {
+        warn!("Failed to inherit categories: {}", error);
+    }
+
+    if let Err(error) = rules::categorize(database, owner, &products, ticket.inn()).await {
+        warn!("Failed to categorize products: {}", error);
+    }
+
+    let warnings = match database.select_budgets(owner, &month).await {
+        Ok(budgets_after) => budgets::overspent(&budgets_before, &budgets_after),
+        Err(error) => {
+            warn!("Failed to check budgets: {}", error);
+
+            Vec::new()
+        }
+    };
+
+    for warning in &warnings {
+        info!("Budget warning: {}", warning);
     }
 
-    Ok(warp::reply::json(&Reply::success()))
+    Ok(Some(warnings))
 }
@@ -0,0 +1,73 @@
+use crate::api_error::ApiError;
+use crate::database::UserData;
+use percent_encoding::percent_decode_str;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Map;
+use serde_json::Value;
+use std::future::ready;
+use std::future::Ready;
+use std::str::FromStr;
+use std::str::Utf8Error;
+use warp::Rejection;
+
+/// Percent-decoded path parameter, warp passes path segments to `FromStr`
+/// as they were sent, so product names and receipt keys are decoded here.
+#[derive(Debug, Clone, Serialize)]
+#[serde(transparent)]
+pub struct Segment(String);
+
+impl FromStr for Segment {
+    type Err = Utf8Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = percent_decode_str(value).decode_utf8()?;
+
+        Ok(Segment(value.into_owned()))
+    }
+}
+
+impl From<Segment> for String {
+    fn from(value: Segment) -> Self {
+        value.0
+    }
+}
+
+/// Future of the user and the params built by [`with_path`] and [`from_path`].
+type Params<T> = Ready<Result<(UserData, T), Rejection>>;
+
+/// Builds the params of a REST route from the JSON body with the path
+/// parameter stored as `key`, so REST routes reuse the params of the
+/// deprecated routes which take everything from the body.
+pub fn with_path<P, T>(
+    key: &'static str,
+) -> impl Fn(P, UserData, Map<String, Value>) -> Params<T> + Clone
+where
+    P: Serialize,
+    T: DeserializeOwned,
+{
+    move |value, user, mut body| {
+        let params = serde_json::to_value(value)
+            .and_then(|value| {
+                body.insert(key.into(), value);
+
+                serde_json::from_value(Value::Object(body))
+            })
+            .map(|params| (user, params))
+            .map_err(|error| warp::reject::custom(ApiError::BadRequest(error.to_string())));
+
+        ready(params)
+    }
+}
+
+/// Builds the params of a REST route without a body from the path parameter
+/// stored as `key`.
+pub fn from_path<P, T>(key: &'static str) -> impl Fn(P, UserData) -> Params<T> + Clone
+where
+    P: Serialize,
+    T: DeserializeOwned,
+{
+    let with_path = with_path(key);
+
+    move |value, user| with_path(value, user, Map::new())
+}
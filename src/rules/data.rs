@@ -0,0 +1,179 @@
+use super::matcher::RuleChange;
+use crate::database::RuleData;
+use serde::Deserialize;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = rules::ReplyRule)]
+pub struct ReplyRule {
+    id: i64,
+    priority: i64,
+    kind: String,
+    pattern: String,
+    inn: Option<String>,
+    category: String,
+    name: String,
+}
+
+impl From<RuleData> for ReplyRule {
+    fn from(value: RuleData) -> Self {
+        Self {
+            id: value.id(),
+            priority: value.priority(),
+            kind: value.kind().into(),
+            pattern: value.pattern().into(),
+            inn: value.inn().cloned(),
+            category: value.category().into(),
+            name: value.name().into(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = rules::ReplyChange)]
+pub struct ReplyChange {
+    rule: i64,
+    product: String,
+    category: String,
+    name: String,
+    new_category: String,
+    new_name: String,
+}
+
+impl From<RuleChange> for ReplyChange {
+    fn from(value: RuleChange) -> Self {
+        Self {
+            rule: value.rule(),
+            product: value.product().into(),
+            category: value.category().cloned().unwrap_or_default(),
+            name: value.name().cloned().unwrap_or_default(),
+            new_category: value.new_category().into(),
+            new_name: value.new_name().into(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(untagged)]
+#[schema(as = rules::Reply)]
+pub enum Reply {
+    List {
+        success: bool,
+        items: Vec<ReplyRule>,
+    },
+    Preview {
+        success: bool,
+        changes: Vec<ReplyChange>,
+    },
+    Apply {
+        success: bool,
+        count: usize,
+    },
+    Success {
+        success: bool,
+    },
+}
+
+impl Reply {
+    pub fn list(items: Vec<RuleData>) -> Self {
+        let items = items.into_iter().map(ReplyRule::from).collect();
+
+        Reply::List {
+            success: true,
+            items,
+        }
+    }
+
+    pub fn preview(changes: Vec<RuleChange>) -> Self {
+        let changes = changes.into_iter().map(ReplyChange::from).collect();
+
+        Reply::Preview {
+            success: true,
+            changes,
+        }
+    }
+
+    pub fn apply(count: usize) -> Self {
+        Reply::Apply {
+            success: true,
+            count,
+        }
+    }
+
+    pub fn success() -> Self {
+        Reply::Success { success: true }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = rules::UpdateParams)]
+pub struct UpdateParams {
+    id: Option<i64>,
+    priority: i64,
+    kind: String,
+    pattern: String,
+    inn: Option<String>,
+    category: String,
+    name: String,
+}
+
+impl UpdateParams {
+    /// Drops the id, so the params create a new rule.
+    pub fn into_new(self) -> Self {
+        Self { id: None, ..self }
+    }
+
+    pub fn id(&self) -> Option<i64> {
+        self.id
+    }
+
+    pub fn priority(&self) -> i64 {
+        self.priority
+    }
+
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    pub fn inn(&self) -> Option<&str> {
+        self.inn.as_deref().filter(|inn| !inn.is_empty())
+    }
+
+    pub fn category(&self) -> &str {
+        &self.category
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = rules::DeleteParams)]
+pub struct DeleteParams {
+    id: i64,
+}
+
+impl DeleteParams {
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = rules::ApplyParams)]
+pub struct ApplyParams {
+    #[serde(default)]
+    overwrite: bool,
+}
+
+impl ApplyParams {
+    pub fn overwrite(&self) -> bool {
+        self.overwrite
+    }
+}
@@ -0,0 +1,176 @@
+use crate::database::ProductData;
+use crate::database::RuleData;
+use receipt_check::Error;
+use regex::Regex;
+use std::collections::HashMap;
+
+#[derive(Debug)]
+enum Matcher {
+    Substring(String),
+    Regex(Regex),
+    Exact(String),
+}
+
+impl Matcher {
+    fn new(kind: &str, pattern: &str) -> Result<Self, Error> {
+        match kind {
+            "substring" => Ok(Matcher::Substring(pattern.to_lowercase())),
+            "regex" => Regex::new(pattern)
+                .map(Matcher::Regex)
+                .map_err(|error| Error::Invalid(error.to_string())),
+            "exact" => Ok(Matcher::Exact(pattern.into())),
+            _ => Err(Error::Invalid(format!("Unknown rule kind: {}", kind))),
+        }
+    }
+
+    fn is_match(&self, product: &str) -> bool {
+        match self {
+            Matcher::Substring(pattern) => product.to_lowercase().contains(pattern),
+            Matcher::Regex(regex) => regex.is_match(product),
+            Matcher::Exact(pattern) => product == pattern,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Rule {
+    id: i64,
+    matcher: Matcher,
+    inn: Option<String>,
+    category: String,
+    name: String,
+}
+
+impl Rule {
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+
+    pub fn category(&self) -> &str {
+        &self.category
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn is_match(&self, product: &str, inns: &[String]) -> bool {
+        let inn_match = match &self.inn {
+            Some(inn) => inns.contains(inn),
+            None => true,
+        };
+
+        inn_match && self.matcher.is_match(product)
+    }
+}
+
+#[derive(Debug)]
+pub struct RuleChange {
+    rule: i64,
+    product: String,
+    category: Option<String>,
+    name: Option<String>,
+    new_category: String,
+    new_name: String,
+}
+
+impl RuleChange {
+    pub fn rule(&self) -> i64 {
+        self.rule
+    }
+
+    pub fn product(&self) -> &str {
+        &self.product
+    }
+
+    pub fn category(&self) -> Option<&String> {
+        self.category.as_ref()
+    }
+
+    pub fn name(&self) -> Option<&String> {
+        self.name.as_ref()
+    }
+
+    pub fn new_category(&self) -> &str {
+        &self.new_category
+    }
+
+    pub fn new_name(&self) -> &str {
+        &self.new_name
+    }
+}
+
+/// Compiled rules ordered by priority, the first matching rule wins.
+#[derive(Debug)]
+pub struct RuleSet {
+    rules: Vec<Rule>,
+}
+
+impl RuleSet {
+    pub fn new(rules: Vec<RuleData>) -> Result<Self, Error> {
+        let mut result = Vec::with_capacity(rules.len());
+
+        for rule in rules {
+            result.push(Rule {
+                id: rule.id(),
+                matcher: Matcher::new(rule.kind(), rule.pattern())?,
+                inn: rule.inn().cloned(),
+                category: rule.category().into(),
+                name: rule.name().into(),
+            });
+        }
+
+        Ok(Self { rules: result })
+    }
+
+    pub fn check(kind: &str, pattern: &str) -> Result<(), Error> {
+        Matcher::new(kind, pattern).map(|_| ())
+    }
+
+    pub fn find(&self, product: &str, inns: &[String]) -> Option<&Rule> {
+        self.rules.iter().find(|rule| rule.is_match(product, inns))
+    }
+
+    /// Evaluates rules against known products. Categorized products are only
+    /// considered when `overwrite` is set.
+    pub fn changes(
+        &self,
+        products: Vec<ProductData>,
+        product_inns: Vec<(String, String)>,
+        overwrite: bool,
+    ) -> Vec<RuleChange> {
+        let mut inns: HashMap<String, Vec<String>> = HashMap::new();
+
+        for (product, inn) in product_inns {
+            inns.entry(product).or_default().push(inn);
+        }
+
+        let mut result = Vec::new();
+
+        for product in products {
+            if !overwrite && product.category().is_some() {
+                continue;
+            }
+
+            let product_inns = inns.get(product.product()).map(Vec::as_slice);
+
+            if let Some(rule) = self.find(product.product(), product_inns.unwrap_or(&[])) {
+                let unchanged = product.category().map(String::as_str) == Some(rule.category())
+                    && product.name().map(String::as_str) == Some(rule.name());
+
+                if !unchanged {
+                    result.push(RuleChange {
+                        rule: rule.id(),
+                        product: product.product().into(),
+                        category: product.category().cloned(),
+                        name: product.name().cloned(),
+                        new_category: rule.category().into(),
+                        new_name: rule.name().into(),
+                    });
+                }
+            }
+        }
+
+        result
+    }
+}
@@ -0,0 +1,228 @@
+mod data;
+mod matcher;
+
+pub use self::data::ApplyParams;
+pub use self::data::DeleteParams;
+pub use self::data::Reply;
+pub use self::data::UpdateParams;
+pub use self::matcher::RuleSet;
+
+use crate::api_error::OrReject;
+use crate::classifier::Classifier;
+use crate::database::Database;
+use crate::database::UserData;
+use std::error::Error;
+use warp::Rejection;
+
+/// Lists categorization rules.
+#[utoipa::path(
+    get,
+    path = "/api/v1/rules",
+    tag = "rules",
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn list(user: UserData, database: Database) -> Result<impl warp::Reply, Rejection> {
+    info!("Request rules");
+
+    let items = database
+        .select_rules(user.owner())
+        .await
+        .or_reject("Failed to read rules")?;
+
+    Ok(warp::reply::json(&Reply::list(items)))
+}
+
+/// Creates a categorization rule.
+#[utoipa::path(
+    post,
+    path = "/api/v1/rules",
+    tag = "rules",
+    request_body = UpdateParams,
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn create(
+    user: UserData,
+    params: UpdateParams,
+    database: Database,
+) -> Result<impl warp::Reply, Rejection> {
+    update(user, params.into_new(), database).await
+}
+
+/// Changes a categorization rule, `id` is taken from the path.
+#[utoipa::path(
+    put,
+    path = "/api/v1/rules/{id}",
+    tag = "rules",
+    params(
+        ("id" = i64, Path, description = "Rule id"),
+    ),
+    request_body = UpdateParams,
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn update(
+    user: UserData,
+    params: UpdateParams,
+    database: Database,
+) -> Result<impl warp::Reply, Rejection> {
+    info!("Request rule update: {:?}", params);
+
+    RuleSet::check(params.kind(), params.pattern()).or_reject("Invalid rule")?;
+    database
+        .update_rule(
+            user.owner(),
+            params.id(),
+            params.priority(),
+            params.kind(),
+            params.pattern(),
+            params.inn(),
+            params.category(),
+            params.name(),
+        )
+        .await
+        .or_reject("Failed to save rule")?;
+
+    Ok(warp::reply::json(&Reply::success()))
+}
+
+/// Removes a categorization rule.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/rules/{id}",
+    tag = "rules",
+    params(
+        ("id" = i64, Path, description = "Rule id"),
+    ),
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn delete(
+    user: UserData,
+    params: DeleteParams,
+    database: Database,
+) -> Result<impl warp::Reply, Rejection> {
+    info!("Request rule delete: {:?}", params);
+
+    database
+        .remove_rule(user.owner(), params.id())
+        .await
+        .or_reject("Failed to delete rule")?;
+
+    Ok(warp::reply::json(&Reply::success()))
+}
+
+/// Lists category changes rules would make.
+#[utoipa::path(
+    post,
+    path = "/api/v1/rules/preview",
+    tag = "rules",
+    request_body = ApplyParams,
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn preview(
+    user: UserData,
+    params: ApplyParams,
+    database: Database,
+) -> Result<impl warp::Reply, Rejection> {
+    info!("Request rules preview: {:?}", params);
+
+    let rules = database
+        .select_rules(user.owner())
+        .await
+        .or_reject("Failed to read rules")?;
+    let rules = RuleSet::new(rules).or_reject("Failed to compile rules")?;
+    let products = database
+        .select_category_names(user.owner())
+        .await
+        .or_reject("Failed to read products")?;
+    let inns = database
+        .select_product_inns(user.owner())
+        .await
+        .or_reject("Failed to read product stores")?;
+    let changes = rules.changes(products, inns, params.overwrite());
+
+    Ok(warp::reply::json(&Reply::preview(changes)))
+}
+
+/// Applies rules to known products.
+#[utoipa::path(
+    post,
+    path = "/api/v1/rules/apply",
+    tag = "rules",
+    request_body = ApplyParams,
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn apply(
+    user: UserData,
+    params: ApplyParams,
+    database: Database,
+    classifier: Classifier,
+) -> Result<impl warp::Reply, Rejection> {
+    info!("Request rules apply: {:?}", params);
+
+    let count = apply_rules(&database, user.owner(), params.overwrite())
+        .await
+        .or_reject("Failed to apply rules")?;
+    let products = database
+        .select_products(user.owner())
+        .await
+        .or_reject("Failed to read products")?;
+    classifier.train(user.owner(), &products).await;
+
+    Ok(warp::reply::json(&Reply::apply(count)))
+}
+
+/// Applies all rules to the known products and saves the changed categories.
+/// Products with a category are changed only when `overwrite` is set.
+pub async fn apply_rules(
+    database: &Database,
+    owner: i64,
+    overwrite: bool,
+) -> Result<usize, Box<dyn Error>> {
+    let rules = RuleSet::new(database.select_rules(owner).await?)?;
+    let products = database.select_category_names(owner).await?;
+    let inns = database.select_product_inns(owner).await?;
+    let changes = rules.changes(products, inns, overwrite);
+
+    let categories: Vec<_> = changes
+        .iter()
+        .map(|change| (change.product(), change.new_category(), change.new_name()))
+        .collect();
+    database
+        .update_product_category_changes(owner, &categories)
+        .await?;
+
+    Ok(changes.len())
+}
+
+/// Assigns categories to the new products of a just scanned ticket. Products
+/// which already have a category are left untouched.
+pub async fn categorize(
+    database: &Database,
+    owner: i64,
+    products: &[&str],
+    inn: Option<&str>,
+) -> Result<usize, Box<dyn Error>> {
+    let rules = RuleSet::new(database.select_rules(owner).await?)?;
+    let inns: Vec<String> = inn.into_iter().map(String::from).collect();
+    let mut count = 0;
+
+    for product in products {
+        if database
+            .select_product_category(owner, product)
+            .await?
+            .is_some()
+        {
+            continue;
+        }
+
+        if let Some(rule) = rules.find(product, &inns) {
+            info!("Product {} matched rule {}", product, rule.id());
+
+            database
+                .update_product_category(owner, product, rule.category(), rule.name())
+                .await?;
+            count += 1;
+        }
+    }
+
+    Ok(count)
+}
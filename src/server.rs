@@ -0,0 +1,257 @@
+use crate::proxy;
+use crate::proxy::Forwarded;
+use crate::proxy::Proxies;
+use crate::proxy::RemoteAddress;
+use hyper::server::conn::Http;
+use hyper::service::service_fn;
+use hyper::service::Service;
+use hyper::Body;
+use hyper::Request;
+use hyper::Response;
+use std::convert::Infallible;
+use std::error::Error;
+use std::fs::File;
+use std::io::BufReader;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::RwLock;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncWrite;
+use tokio::net::TcpListener;
+use tokio_rustls::rustls::ServerConfig;
+use tokio_rustls::TlsAcceptor;
+
+const RELOAD_INTERVAL: Duration = Duration::from_secs(10);
+
+/// TLS configuration read from the key and certificate files. The files are
+/// read again on SIGHUP or when they change, new connections use the new
+/// configuration while established connections keep the old one.
+#[derive(Clone)]
+pub struct Tls {
+    key: PathBuf,
+    certificate: PathBuf,
+    config: Arc<RwLock<Arc<ServerConfig>>>,
+}
+
+impl Tls {
+    pub fn new(key: &Path, certificate: &Path) -> Result<Self, Box<dyn Error>> {
+        let config = load(key, certificate)?;
+
+        Ok(Self {
+            key: key.to_path_buf(),
+            certificate: certificate.to_path_buf(),
+            config: Arc::new(RwLock::new(Arc::new(config))),
+        })
+    }
+
+    fn acceptor(&self) -> TlsAcceptor {
+        let config = self.config.read().expect("TLS configuration lock poisoned");
+
+        TlsAcceptor::from(config.clone())
+    }
+
+    fn reload(&self) {
+        match load(&self.key, &self.certificate) {
+            Ok(config) => {
+                info!("Reloaded certificate {}", self.certificate.display());
+
+                *self
+                    .config
+                    .write()
+                    .expect("TLS configuration lock poisoned") = Arc::new(config);
+            }
+            Err(error) => warn!("Failed to reload certificate: {}", error),
+        }
+    }
+
+    fn modified(&self) -> Option<(SystemTime, SystemTime)> {
+        let modified = |path: &Path| path.metadata().and_then(|meta| meta.modified()).ok();
+
+        Some((modified(&self.key)?, modified(&self.certificate)?))
+    }
+
+    /// Reloads the configuration when the files change or SIGHUP is received.
+    async fn watch(self) {
+        let mut modified = self.modified();
+        let mut interval = tokio::time::interval(RELOAD_INTERVAL);
+        let mut hangup = hangup();
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    let current = self.modified();
+
+                    if current.is_some() && current != modified {
+                        modified = current;
+                        self.reload();
+                    }
+                }
+                _ = hangup.recv() => {
+                    modified = self.modified();
+                    self.reload();
+                }
+            }
+        }
+    }
+}
+
+/// Serves the service with TLS when configured, or plain HTTP otherwise.
+pub async fn serve<S>(
+    service: S,
+    address: SocketAddr,
+    tls: Option<Tls>,
+    proxies: Proxies,
+) -> Result<(), Box<dyn Error>>
+where
+    S: Service<Request<Body>, Response = Response<Body>, Error = Infallible>
+        + Clone
+        + Send
+        + 'static,
+    S::Future: Send,
+{
+    let listener = TcpListener::bind(address).await?;
+
+    match &tls {
+        Some(tls) => {
+            info!("Listening on https://{}", address);
+
+            tokio::spawn(tls.clone().watch());
+        }
+        None => info!("Listening on http://{}", address),
+    }
+
+    loop {
+        let (stream, remote) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(error) => {
+                warn!("Failed to accept connection: {}", error);
+
+                continue;
+            }
+        };
+        let service = service.clone();
+        let proxies = proxies.clone();
+
+        match &tls {
+            Some(tls) => {
+                let acceptor = tls.acceptor();
+
+                tokio::spawn(async move {
+                    match acceptor.accept(stream).await {
+                        Ok(stream) => connection(stream, remote, proxies, service).await,
+                        Err(error) => debug!("TLS handshake with {} failed: {}", remote, error),
+                    }
+                });
+            }
+            None => {
+                tokio::spawn(connection(stream, remote, proxies, service));
+            }
+        }
+    }
+}
+
+async fn connection<I, S>(stream: I, remote: SocketAddr, proxies: Proxies, service: S)
+where
+    I: AsyncRead + AsyncWrite + Send + Unpin + 'static,
+    S: Service<Request<Body>, Response = Response<Body>, Error = Infallible>
+        + Clone
+        + Send
+        + 'static,
+    S::Future: Send,
+{
+    let service = service_fn(move |mut request: Request<Body>| {
+        let forwarded = Forwarded::new(&proxies, Some(remote), request.headers());
+        let method = request.method().clone();
+        let uri = request.uri().clone();
+        let version = request.version();
+        let started = Instant::now();
+        let mut service = service.clone();
+
+        request.extensions_mut().insert(RemoteAddress(remote));
+
+        async move {
+            let response = service.call(request).await?;
+
+            proxy::log(
+                &forwarded,
+                &method,
+                &uri,
+                version,
+                response.status(),
+                started.elapsed(),
+            );
+
+            Ok::<_, Infallible>(response)
+        }
+    });
+
+    if let Err(error) = Http::new().serve_connection(stream, service).await {
+        debug!("Connection with {} failed: {}", remote, error);
+    }
+}
+
+fn load(key: &Path, certificate: &Path) -> Result<ServerConfig, Box<dyn Error>> {
+    let certificates = rustls_pemfile::certs(&mut BufReader::new(File::open(certificate)?))
+        .collect::<Result<Vec<_>, _>>()?;
+    let key = rustls_pemfile::private_key(&mut BufReader::new(File::open(key)?))?
+        .ok_or_else(|| format!("Private key not found in {}", key.display()))?;
+    let mut config = ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(certificates, key)?;
+
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+    Ok(config)
+}
+
+#[cfg(unix)]
+fn hangup() -> Hangup {
+    use tokio::signal::unix::signal;
+    use tokio::signal::unix::SignalKind;
+
+    match signal(SignalKind::hangup()) {
+        Ok(signal) => Hangup(Some(signal)),
+        Err(error) => {
+            warn!("Failed to listen for SIGHUP: {}", error);
+
+            Hangup(None)
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn hangup() -> Hangup {
+    Hangup
+}
+
+#[cfg(unix)]
+struct Hangup(Option<tokio::signal::unix::Signal>);
+
+#[cfg(unix)]
+impl Hangup {
+    async fn recv(&mut self) {
+        match &mut self.0 {
+            Some(signal) => {
+                if signal.recv().await.is_none() {
+                    self.0 = None;
+                }
+            }
+            None => std::future::pending().await,
+        }
+    }
+}
+
+#[cfg(not(unix))]
+struct Hangup;
+
+#[cfg(not(unix))]
+impl Hangup {
+    async fn recv(&mut self) {
+        std::future::pending().await
+    }
+}
@@ -0,0 +1,243 @@
+use super::settlement::Balance;
+use super::settlement::Transfer;
+use crate::database::PersonData;
+use crate::database::SplitItemData;
+use crate::database::SplitReceiptData;
+use serde::Deserialize;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = splits::ReplyPerson)]
+pub struct ReplyPerson {
+    id: i64,
+    name: String,
+}
+
+impl From<PersonData> for ReplyPerson {
+    fn from(value: PersonData) -> Self {
+        Self {
+            id: value.id(),
+            name: value.name().into(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = splits::ReplyShare)]
+pub struct ReplyShare {
+    person: i64,
+    share: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = splits::ReplyItem)]
+pub struct ReplyItem {
+    id: i64,
+    product: String,
+    sum: f64,
+    shares: Vec<ReplyShare>,
+}
+
+impl From<&SplitItemData> for ReplyItem {
+    fn from(value: &SplitItemData) -> Self {
+        Self {
+            id: value.id(),
+            product: value.product().into(),
+            sum: value.sum(),
+            shares: value
+                .shares()
+                .iter()
+                .map(|(person, share)| ReplyShare {
+                    person: *person,
+                    share: *share,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = splits::ReplyReceipt)]
+pub struct ReplyReceipt {
+    ticket: String,
+    date: String,
+    seller: Option<String>,
+    payer: Option<i64>,
+    items: Vec<ReplyItem>,
+}
+
+impl From<SplitReceiptData> for ReplyReceipt {
+    fn from(value: SplitReceiptData) -> Self {
+        Self {
+            ticket: value.ticket().into(),
+            date: value.date().into(),
+            seller: value.seller().cloned(),
+            payer: value.payer(),
+            items: value.items().iter().map(ReplyItem::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = splits::ReplyBalance)]
+pub struct ReplyBalance {
+    person: i64,
+    name: String,
+    paid: f64,
+    spent: f64,
+    balance: f64,
+}
+
+impl From<Balance> for ReplyBalance {
+    fn from(value: Balance) -> Self {
+        Self {
+            person: value.person(),
+            name: value.name().into(),
+            paid: value.paid(),
+            spent: value.spent(),
+            balance: value.balance(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = splits::ReplyTransfer)]
+pub struct ReplyTransfer {
+    from: i64,
+    to: i64,
+    amount: f64,
+}
+
+impl From<Transfer> for ReplyTransfer {
+    fn from(value: Transfer) -> Self {
+        Self {
+            from: value.from(),
+            to: value.to(),
+            amount: value.amount(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(untagged)]
+#[schema(as = splits::Reply)]
+pub enum Reply {
+    People {
+        success: bool,
+        items: Vec<ReplyPerson>,
+    },
+    Receipts {
+        success: bool,
+        receipts: Vec<ReplyReceipt>,
+    },
+    Balance {
+        success: bool,
+        balances: Vec<ReplyBalance>,
+        transfers: Vec<ReplyTransfer>,
+    },
+    Created {
+        success: bool,
+        id: i64,
+    },
+    Success {
+        success: bool,
+    },
+}
+
+impl Reply {
+    pub fn people(items: Vec<PersonData>) -> Self {
+        let items = items.into_iter().map(ReplyPerson::from).collect();
+
+        Reply::People {
+            success: true,
+            items,
+        }
+    }
+
+    pub fn receipts(receipts: Vec<SplitReceiptData>) -> Self {
+        let receipts = receipts.into_iter().map(ReplyReceipt::from).collect();
+
+        Reply::Receipts {
+            success: true,
+            receipts,
+        }
+    }
+
+    pub fn balance(balances: Vec<Balance>, transfers: Vec<Transfer>) -> Self {
+        Reply::Balance {
+            success: true,
+            balances: balances.into_iter().map(ReplyBalance::from).collect(),
+            transfers: transfers.into_iter().map(ReplyTransfer::from).collect(),
+        }
+    }
+
+    pub fn created(id: i64) -> Self {
+        Reply::Created { success: true, id }
+    }
+
+    pub fn success() -> Self {
+        Reply::Success { success: true }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = splits::PersonParams)]
+pub struct PersonParams {
+    name: String,
+}
+
+impl PersonParams {
+    pub fn name(&self) -> &str {
+        self.name.trim()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = splits::DeleteParams)]
+pub struct DeleteParams {
+    id: i64,
+}
+
+impl DeleteParams {
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = splits::ShareParams)]
+pub struct ShareParams {
+    item: i64,
+    shares: Vec<ReplyShare>,
+}
+
+impl ShareParams {
+    pub fn item(&self) -> i64 {
+        self.item
+    }
+
+    pub fn shares(&self) -> Vec<(i64, f64)> {
+        self.shares
+            .iter()
+            .map(|share| (share.person, share.share))
+            .collect()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = splits::PayerParams)]
+pub struct PayerParams {
+    ticket: String,
+    person: Option<i64>,
+}
+
+impl PayerParams {
+    pub fn ticket(&self) -> &str {
+        &self.ticket
+    }
+
+    pub fn person(&self) -> Option<i64> {
+        self.person
+    }
+}
@@ -0,0 +1,180 @@
+mod data;
+mod settlement;
+
+pub use self::data::DeleteParams;
+pub use self::data::PayerParams;
+pub use self::data::PersonParams;
+pub use self::data::Reply;
+pub use self::data::ShareParams;
+
+use crate::api_error::ApiError;
+use crate::api_error::OrReject;
+use crate::database::Database;
+use crate::database::UserData;
+use warp::Rejection;
+
+/// Lists people sharing expenses.
+#[utoipa::path(
+    get,
+    path = "/api/v1/splits/people",
+    tag = "splits",
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn people(user: UserData, database: Database) -> Result<impl warp::Reply, Rejection> {
+    info!("Request people");
+
+    let items = database
+        .select_people(user.owner())
+        .await
+        .or_reject("Failed to read people")?;
+
+    Ok(warp::reply::json(&Reply::people(items)))
+}
+
+/// Adds a person sharing expenses.
+#[utoipa::path(
+    post,
+    path = "/api/v1/splits/people",
+    tag = "splits",
+    request_body = PersonParams,
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn create_person(
+    user: UserData,
+    params: PersonParams,
+    database: Database,
+) -> Result<impl warp::Reply, Rejection> {
+    info!("Request person create: {:?}", params);
+
+    if params.name().is_empty() {
+        return Err(warp::reject::custom(ApiError::BadRequest(
+            "Person name is empty".into(),
+        )));
+    }
+
+    let id = database
+        .insert_person(user.owner(), params.name())
+        .await
+        .or_reject("Failed to save person")?;
+
+    Ok(warp::reply::json(&Reply::created(id)))
+}
+
+/// Removes a person sharing expenses.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/splits/people/{id}",
+    tag = "splits",
+    params(
+        ("id" = i64, Path, description = "Person id"),
+    ),
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn delete_person(
+    user: UserData,
+    params: DeleteParams,
+    database: Database,
+) -> Result<impl warp::Reply, Rejection> {
+    info!("Request person delete: {:?}", params);
+
+    database
+        .remove_person(user.owner(), params.id())
+        .await
+        .or_reject("Failed to delete person")?;
+
+    Ok(warp::reply::json(&Reply::success()))
+}
+
+/// Lists receipts with payers and item shares.
+#[utoipa::path(
+    get,
+    path = "/api/v1/splits/receipts",
+    tag = "splits",
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn receipts(user: UserData, database: Database) -> Result<impl warp::Reply, Rejection> {
+    info!("Request split receipts");
+
+    let receipts = database
+        .select_split_receipts(user.owner())
+        .await
+        .or_reject("Failed to read receipts")?;
+
+    Ok(warp::reply::json(&Reply::receipts(receipts)))
+}
+
+/// Sets shares of a receipt item, `item` is taken from the path.
+#[utoipa::path(
+    put,
+    path = "/api/v1/splits/items/{id}/shares",
+    tag = "splits",
+    params(
+        ("id" = i64, Path, description = "Receipt item id"),
+    ),
+    request_body = ShareParams,
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn share(
+    user: UserData,
+    params: ShareParams,
+    database: Database,
+) -> Result<impl warp::Reply, Rejection> {
+    info!("Request item shares: {:?}", params);
+
+    database
+        .update_item_shares(user.owner(), params.item(), &params.shares())
+        .await
+        .or_reject("Failed to save shares")?;
+
+    Ok(warp::reply::json(&Reply::success()))
+}
+
+/// Sets the payer of a receipt, `ticket` is taken from the path.
+#[utoipa::path(
+    put,
+    path = "/api/v1/splits/receipts/{key}/payer",
+    tag = "splits",
+    params(
+        ("key" = String, Path, description = "Receipt key"),
+    ),
+    request_body = PayerParams,
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn payer(
+    user: UserData,
+    params: PayerParams,
+    database: Database,
+) -> Result<impl warp::Reply, Rejection> {
+    info!("Request receipt payer: {:?}", params);
+
+    database
+        .update_receipt_payer(user.owner(), params.ticket(), params.person())
+        .await
+        .or_reject("Failed to save payer")?;
+
+    Ok(warp::reply::json(&Reply::success()))
+}
+
+/// Returns balances and transfers settling them.
+#[utoipa::path(
+    get,
+    path = "/api/v1/splits/balance",
+    tag = "splits",
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn balance(user: UserData, database: Database) -> Result<impl warp::Reply, Rejection> {
+    info!("Request split balance");
+
+    let people = database
+        .select_people(user.owner())
+        .await
+        .or_reject("Failed to read people")?;
+    let receipts = database
+        .select_split_receipts(user.owner())
+        .await
+        .or_reject("Failed to read receipts")?;
+    let balances = settlement::balances(&people, &receipts);
+    let transfers = settlement::settlements(&balances);
+
+    Ok(warp::reply::json(&Reply::balance(balances, transfers)))
+}
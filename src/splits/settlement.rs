@@ -0,0 +1,235 @@
+use crate::database::PersonData;
+use crate::database::SplitReceiptData;
+use std::collections::HashMap;
+
+#[derive(Debug)]
+pub struct Balance {
+    person: i64,
+    name: String,
+    paid: f64,
+    spent: f64,
+}
+
+impl Balance {
+    pub fn person(&self) -> i64 {
+        self.person
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn paid(&self) -> f64 {
+        self.paid
+    }
+
+    pub fn spent(&self) -> f64 {
+        self.spent
+    }
+
+    /// Positive balance is owed to the person, negative is owed by the person.
+    pub fn balance(&self) -> f64 {
+        self.paid - self.spent
+    }
+}
+
+#[derive(Debug)]
+pub struct Transfer {
+    from: i64,
+    to: i64,
+    amount: f64,
+}
+
+impl Transfer {
+    pub fn from(&self) -> i64 {
+        self.from
+    }
+
+    pub fn to(&self) -> i64 {
+        self.to
+    }
+
+    pub fn amount(&self) -> f64 {
+        self.amount
+    }
+}
+
+/// Computes what every person paid and spent. Only items with shares of
+/// receipts with a known payer are counted, the payer pays the split part of
+/// the receipt and every item sum is divided proportionally to the shares.
+pub fn balances(people: &[PersonData], receipts: &[SplitReceiptData]) -> Vec<Balance> {
+    let mut paid: HashMap<i64, f64> = HashMap::new();
+    let mut spent: HashMap<i64, f64> = HashMap::new();
+
+    for receipt in receipts {
+        let payer = match receipt.payer() {
+            Some(payer) => payer,
+            None => continue,
+        };
+
+        for item in receipt.items() {
+            let total: f64 = item.shares().iter().map(|(_, share)| share).sum();
+
+            if total <= 0.0 {
+                continue;
+            }
+
+            *paid.entry(payer).or_default() += item.sum();
+
+            for (person, share) in item.shares() {
+                *spent.entry(*person).or_default() += item.sum() * share / total;
+            }
+        }
+    }
+
+    people
+        .iter()
+        .map(|person| Balance {
+            person: person.id(),
+            name: person.name().into(),
+            paid: paid.get(&person.id()).copied().unwrap_or_default(),
+            spent: spent.get(&person.id()).copied().unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// Suggests transfers settling all balances. The largest debtor pays the
+/// largest creditor until both lists are exhausted, which needs at most one
+/// transfer less than the number of people. Amounts are rounded to cents.
+pub fn settlements(balances: &[Balance]) -> Vec<Transfer> {
+    let cents = |balance: &Balance| (balance.balance() * 100.0).round() as i64;
+    let mut creditors: Vec<(i64, i64)> = balances
+        .iter()
+        .map(|balance| (balance.person(), cents(balance)))
+        .filter(|(_, amount)| *amount > 0)
+        .collect();
+    let mut debtors: Vec<(i64, i64)> = balances
+        .iter()
+        .map(|balance| (balance.person(), -cents(balance)))
+        .filter(|(_, amount)| *amount > 0)
+        .collect();
+    let mut result = Vec::new();
+
+    creditors.sort_by_key(|(_, amount)| *amount);
+    debtors.sort_by_key(|(_, amount)| *amount);
+
+    while let (Some((creditor, credit)), Some((debtor, debt))) = (creditors.pop(), debtors.pop()) {
+        let amount = credit.min(debt);
+
+        result.push(Transfer {
+            from: debtor,
+            to: creditor,
+            amount: amount as f64 / 100.0,
+        });
+
+        if credit > amount {
+            insert_sorted(&mut creditors, (creditor, credit - amount));
+        }
+
+        if debt > amount {
+            insert_sorted(&mut debtors, (debtor, debt - amount));
+        }
+    }
+
+    result
+}
+
+fn insert_sorted(items: &mut Vec<(i64, i64)>, item: (i64, i64)) {
+    let index = items.partition_point(|(_, amount)| *amount < item.1);
+    items.insert(index, item);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::SplitItemData;
+
+    fn people(names: &[&str]) -> Vec<PersonData> {
+        names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| PersonData::new(index as i64 + 1, name.to_string()))
+            .collect()
+    }
+
+    fn receipt(payer: i64, sum: f64, shares: &[(i64, f64)]) -> SplitReceiptData {
+        let mut item = SplitItemData::new(1, "Product".into(), sum);
+
+        for (person, share) in shares {
+            item.push_share(*person, *share);
+        }
+
+        let mut receipt =
+            SplitReceiptData::new("key".into(), "2026.01.01".into(), None, Some(payer));
+        receipt.push_item(item);
+        receipt
+    }
+
+    fn transfers(transfers: &[Transfer]) -> Vec<(i64, i64, f64)> {
+        transfers
+            .iter()
+            .map(|transfer| (transfer.from(), transfer.to(), transfer.amount()))
+            .collect()
+    }
+
+    #[test]
+    fn two_people_settle_one_debt() {
+        let people = people(&["Alice", "Bob"]);
+        let receipts = [receipt(1, 100.0, &[(1, 1.0), (2, 1.0)])];
+        let balances = balances(&people, &receipts);
+
+        assert_eq!(balances[0].balance(), 50.0);
+        assert_eq!(balances[1].balance(), -50.0);
+        assert_eq!(transfers(&settlements(&balances)), vec![(2, 1, 50.0)]);
+    }
+
+    #[test]
+    fn three_people_with_cancelling_balances() {
+        let people = people(&["Alice", "Bob", "Carol"]);
+        let shares = [(1, 1.0), (2, 1.0), (3, 1.0)];
+        let receipts = [
+            receipt(1, 90.0, &shares),
+            receipt(2, 90.0, &shares),
+            receipt(3, 90.0, &shares),
+        ];
+        let balances = balances(&people, &receipts);
+
+        for balance in &balances {
+            assert_eq!(balance.paid(), 90.0);
+            assert!((balance.spent() - 90.0).abs() < 1e-9);
+        }
+
+        assert!(settlements(&balances).is_empty());
+    }
+
+    #[test]
+    fn three_people_pay_one_creditor() {
+        let people = people(&["Alice", "Bob", "Carol"]);
+        let receipts = [
+            receipt(1, 300.0, &[(1, 1.0), (2, 1.0), (3, 1.0)]),
+            receipt(2, 60.0, &[(3, 1.0)]),
+        ];
+        let balances = balances(&people, &receipts);
+
+        assert_eq!(
+            transfers(&settlements(&balances)),
+            vec![(3, 1, 160.0), (2, 1, 40.0)]
+        );
+    }
+
+    #[test]
+    fn amounts_are_rounded_to_kopecks() {
+        let people = people(&["Alice", "Bob", "Carol"]);
+        let receipts = [receipt(1, 100.0, &[(1, 1.0), (2, 1.0), (3, 1.0)])];
+        let balances = balances(&people, &receipts);
+        let result = transfers(&settlements(&balances));
+
+        assert_eq!(result.len(), 2);
+
+        for (from, to, amount) in result {
+            assert!(from == 2 || from == 3);
+            assert_eq!(to, 1);
+            assert_eq!(amount, 33.33);
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use super::report::ProductComparison;
+use super::report::StorePrice;
+use serde::Deserialize;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = stores::ReplyStore)]
+pub struct ReplyStore {
+    store: String,
+    seller: String,
+    latest_date: String,
+    latest_price: f64,
+    average_price: f64,
+    purchases: usize,
+    cheapest: bool,
+}
+
+impl From<&StorePrice> for ReplyStore {
+    fn from(value: &StorePrice) -> Self {
+        Self {
+            store: value.store().into(),
+            seller: value.seller().cloned().unwrap_or_default(),
+            latest_date: value.latest_date().into(),
+            latest_price: value.latest_price(),
+            average_price: value.average_price(),
+            purchases: value.purchases(),
+            cheapest: value.cheapest(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = stores::ReplyItem)]
+pub struct ReplyItem {
+    id: i64,
+    title: String,
+    unit: String,
+    stores: Vec<ReplyStore>,
+}
+
+impl From<ProductComparison> for ReplyItem {
+    fn from(value: ProductComparison) -> Self {
+        Self {
+            id: value.id(),
+            title: value.title().into(),
+            unit: value.unit().into(),
+            stores: value.stores().iter().map(ReplyStore::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(untagged)]
+#[schema(as = stores::Reply)]
+pub enum Reply {
+    List {
+        success: bool,
+        items: Vec<ReplyItem>,
+    },
+}
+
+impl Reply {
+    pub fn list(items: Vec<ProductComparison>) -> Self {
+        let items = items.into_iter().map(ReplyItem::from).collect();
+
+        Reply::List {
+            success: true,
+            items,
+        }
+    }
+}
@@ -0,0 +1,28 @@
+mod data;
+mod report;
+
+pub use self::data::Reply;
+
+use crate::api_error::OrReject;
+use crate::database::Database;
+use crate::database::UserData;
+use warp::Rejection;
+
+/// Compares prices of products in different stores.
+#[utoipa::path(
+    get,
+    path = "/api/v1/stores/compare",
+    tag = "products",
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn compare(user: UserData, database: Database) -> Result<impl warp::Reply, Rejection> {
+    info!("Request store comparison");
+
+    let prices = database
+        .select_store_prices(user.owner())
+        .await
+        .or_reject("Failed to read prices")?;
+    let items = report::compare(prices);
+
+    Ok(warp::reply::json(&Reply::list(items)))
+}
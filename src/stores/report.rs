@@ -0,0 +1,151 @@
+use crate::database::StorePriceData;
+
+#[derive(Debug)]
+pub struct StorePrice {
+    inn: Option<String>,
+    store: String,
+    seller: Option<String>,
+    latest_date: String,
+    latest_price: f64,
+    average_price: f64,
+    purchases: usize,
+    cheapest: bool,
+}
+
+impl StorePrice {
+    pub fn store(&self) -> &str {
+        &self.store
+    }
+
+    pub fn seller(&self) -> Option<&String> {
+        self.seller.as_ref()
+    }
+
+    pub fn latest_date(&self) -> &str {
+        &self.latest_date
+    }
+
+    pub fn latest_price(&self) -> f64 {
+        self.latest_price
+    }
+
+    pub fn average_price(&self) -> f64 {
+        self.average_price
+    }
+
+    pub fn purchases(&self) -> usize {
+        self.purchases
+    }
+
+    pub fn cheapest(&self) -> bool {
+        self.cheapest
+    }
+}
+
+#[derive(Debug)]
+pub struct ProductComparison {
+    id: i64,
+    title: String,
+    unit: String,
+    stores: Vec<StorePrice>,
+}
+
+impl ProductComparison {
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn unit(&self) -> &str {
+        &self.unit
+    }
+
+    pub fn stores(&self) -> &[StorePrice] {
+        &self.stores
+    }
+}
+
+/// Groups purchases by product and unit and then by store, only products
+/// bought in more than one store are reported. Prices of a product in
+/// different units are compared separately. The store with the lowest latest
+/// unit price is marked as the cheapest one.
+pub fn compare(prices: Vec<StorePriceData>) -> Vec<ProductComparison> {
+    let mut result: Vec<ProductComparison> = Vec::new();
+    let mut sums: Vec<(f64, f64)> = Vec::new();
+
+    for price in prices {
+        let product = result.last().map(|item| (item.id, item.unit.as_str()));
+
+        if product != Some((price.canonical(), price.unit())) {
+            finish(&mut result, &mut sums);
+            result.push(ProductComparison {
+                id: price.canonical(),
+                title: price.title().into(),
+                unit: price.unit().into(),
+                stores: Vec::new(),
+            });
+        }
+
+        let product = result.last_mut().unwrap();
+        let unit_price = price.sum() / price.unit_quantity();
+
+        match product.stores.last_mut() {
+            Some(store) if store.inn.as_ref() == price.inn() && store.store == price.store() => {
+                store.latest_date = price.date().into();
+                store.latest_price = unit_price;
+                store.purchases += 1;
+
+                let (sum, quantity) = sums.last_mut().unwrap();
+                *sum += price.sum();
+                *quantity += price.unit_quantity();
+            }
+            _ => {
+                product.stores.push(StorePrice {
+                    inn: price.inn().cloned(),
+                    store: price.store().into(),
+                    seller: price.seller().cloned(),
+                    latest_date: price.date().into(),
+                    latest_price: unit_price,
+                    average_price: unit_price,
+                    purchases: 1,
+                    cheapest: false,
+                });
+                sums.push((price.sum(), price.unit_quantity()));
+            }
+        }
+    }
+
+    finish(&mut result, &mut sums);
+    result.sort_by(|a, b| a.title.cmp(&b.title));
+
+    result
+}
+
+fn finish(result: &mut Vec<ProductComparison>, sums: &mut Vec<(f64, f64)>) {
+    let product = match result.last_mut() {
+        Some(product) => product,
+        None => return,
+    };
+
+    for (store, (sum, quantity)) in product.stores.iter_mut().zip(sums.drain(..)) {
+        store.average_price = sum / quantity;
+    }
+
+    if product.stores.len() < 2 {
+        result.pop();
+
+        return;
+    }
+
+    let cheapest = product
+        .stores
+        .iter_mut()
+        .min_by(|a, b| a.latest_price.total_cmp(&b.latest_price));
+
+    if let Some(store) = cheapest {
+        store.cheapest = true;
+    }
+}
@@ -1,22 +1,34 @@
+use crate::database::ReceiptData;
 use crate::database::TicketItemData;
 use serde::Deserialize;
 use serde::Serialize;
+use utoipa::ToSchema;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(tag = "type")]
+#[schema(as = tickets::ReplyItem)]
 pub enum ReplyItem {
     Categorized {
         date: String,
+        product: String,
         category: String,
         name: String,
         quantity: f64,
         sum: f64,
+        unit: Option<String>,
+        unit_price: Option<f64>,
+        ticket: String,
+        member: Option<String>,
     },
     Uncategorized {
         date: String,
         product: String,
         quantity: f64,
         sum: f64,
+        unit: Option<String>,
+        unit_price: Option<f64>,
+        ticket: String,
+        member: Option<String>,
     },
 }
 
@@ -25,34 +37,99 @@ impl From<TicketItemData> for ReplyItem {
         match (value.category(), value.name()) {
             (Some(category), Some(name)) => ReplyItem::Categorized {
                 date: value.date().into(),
+                product: value.product().into(),
                 category: category.clone(),
                 name: name.clone(),
                 quantity: value.quantity(),
                 sum: value.sum(),
+                unit: value.unit().cloned(),
+                unit_price: value.unit_price(),
+                ticket: value.ticket().into(),
+                member: value.member().cloned(),
             },
             _ => ReplyItem::Uncategorized {
                 date: value.date().into(),
                 product: value.product().into(),
                 quantity: value.quantity(),
                 sum: value.sum(),
+                unit: value.unit().cloned(),
+                unit_price: value.unit_price(),
+                ticket: value.ticket().into(),
+                member: value.member().cloned(),
             },
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Receipt header with its items. The total of receipts scanned before
+/// headers were stored is the sum of the items, other unknown fields are
+/// `null`.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = tickets::ReplyReceipt)]
+pub struct ReplyReceipt {
+    ticket: String,
+    /// Purchase time like `2024-01-01T12:00:00`.
+    time: Option<String>,
+    inn: Option<String>,
+    seller: Option<String>,
+    address: Option<String>,
+    total: f64,
+    cash: Option<f64>,
+    card: Option<f64>,
+    fiscal_drive: Option<String>,
+    fiscal_document: Option<String>,
+    fiscal_sign: Option<String>,
+    register: Option<String>,
+    /// Unix time of the scan.
+    scanned: Option<i64>,
+    member: Option<String>,
+    items: Vec<ReplyItem>,
+}
+
+impl From<ReceiptData> for ReplyReceipt {
+    fn from(value: ReceiptData) -> Self {
+        let total = value
+            .total()
+            .unwrap_or_else(|| value.items().iter().map(|item| item.sum()).sum());
+
+        Self {
+            ticket: value.ticket().into(),
+            time: value.time().map(String::from),
+            inn: value.inn().map(String::from),
+            seller: value.seller().map(String::from),
+            address: value.address().map(String::from),
+            total,
+            cash: value.cash(),
+            card: value.card(),
+            fiscal_drive: value.fiscal_drive().map(String::from),
+            fiscal_document: value.fiscal_document().map(String::from),
+            fiscal_sign: value.fiscal_sign().map(String::from),
+            register: value.register().map(String::from),
+            scanned: value.scanned(),
+            member: value.member().map(String::from),
+            items: value
+                .into_items()
+                .into_iter()
+                .map(ReplyItem::from)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(untagged)]
+#[schema(as = tickets::Reply)]
 pub enum Reply {
     List {
         success: bool,
         items: Vec<ReplyItem>,
     },
-    Success {
+    Receipt {
         success: bool,
+        receipt: Box<ReplyReceipt>,
     },
-    Error {
+    Success {
         success: bool,
-        message: String,
     },
 }
 
@@ -66,14 +143,26 @@ impl Reply {
         }
     }
 
+    pub fn receipt(receipt: ReceiptData) -> Self {
+        Reply::Receipt {
+            success: true,
+            receipt: Box::new(receipt.into()),
+        }
+    }
+
     pub fn success() -> Self {
         Reply::Success { success: true }
     }
+}
 
-    pub fn error(message: &str) -> Self {
-        Reply::Error {
-            success: false,
-            message: message.into(),
-        }
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = tickets::DeleteParams)]
+pub struct DeleteParams {
+    ticket: String,
+}
+
+impl DeleteParams {
+    pub fn ticket(&self) -> &str {
+        &self.ticket
     }
 }
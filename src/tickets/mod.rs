@@ -1,41 +1,95 @@
 mod data;
 
+pub use self::data::DeleteParams;
 pub use self::data::Reply;
-pub use self::data::ReplyItem;
 
+use crate::api_error::OrReject;
 use crate::database::Database;
-use std::convert::Infallible;
+use crate::database::UserData;
+use warp::Rejection;
 
-macro_rules! no_fail {
-    ($message:expr, $callback:expr) => {
-        match $callback {
-            Ok(result) => result,
-            Err(error) => {
-                warn!("{}: {}", $message, error);
+/// Lists items of all receipts.
+#[utoipa::path(
+    get,
+    path = "/api/v1/receipts",
+    tag = "receipts",
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn list(user: UserData, database: Database) -> Result<impl warp::Reply, Rejection> {
+    info!("Request ticket list");
 
-                let message = format!("{}", error);
+    let items = database
+        .select_ticket_items(user.owner())
+        .await
+        .or_reject("Failed to read items")?;
 
-                return Ok(warp::reply::json(&Reply::error(&message)));
-            }
-        }
-    };
+    Ok(warp::reply::json(&Reply::list(items)))
 }
 
-pub async fn list(database: Database) -> Result<impl warp::Reply, Infallible> {
-    info!("Request ticket list");
+/// Shows the header, the items and the scan time of one receipt.
+#[utoipa::path(
+    get,
+    path = "/api/v1/receipts/{key}",
+    tag = "receipts",
+    params(
+        ("key" = String, Path, description = "Receipt key"),
+    ),
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn receipt(
+    ticket: String,
+    user: UserData,
+    database: Database,
+) -> Result<impl warp::Reply, Rejection> {
+    info!("Request receipt: {}", ticket);
 
-    let items = no_fail!("Failed to read items", database.select_ticket_items().await);
+    let receipt = database
+        .select_receipt(user.owner(), &ticket)
+        .await
+        .or_reject("Failed to read receipt")?;
 
-    Ok(warp::reply::json(&Reply::list(items)))
+    Ok(warp::reply::json(&Reply::receipt(receipt)))
 }
 
-pub async fn clear(database: Database) -> Result<impl warp::Reply, Infallible> {
+/// Removes all receipts of the household.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/receipts",
+    tag = "receipts",
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn clear(user: UserData, database: Database) -> Result<impl warp::Reply, Rejection> {
     info!("Request tickets clear");
 
-    no_fail!(
-        "Failed to clear items",
-        database.remove_ticket_items().await
-    );
+    database
+        .remove_ticket_items(user.owner())
+        .await
+        .or_reject("Failed to clear items")?;
+
+    Ok(warp::reply::json(&Reply::success()))
+}
+
+/// Removes a receipt.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/receipts/{key}",
+    tag = "receipts",
+    params(
+        ("key" = String, Path, description = "Receipt key"),
+    ),
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn delete(
+    user: UserData,
+    params: DeleteParams,
+    database: Database,
+) -> Result<impl warp::Reply, Rejection> {
+    info!("Request receipt delete: {:?}", params);
+
+    database
+        .remove_receipt(user.owner(), params.ticket())
+        .await
+        .or_reject("Failed to delete receipt")?;
 
     Ok(warp::reply::json(&Reply::success()))
 }
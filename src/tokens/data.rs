@@ -0,0 +1,96 @@
+use crate::database::TokenData;
+use serde::Deserialize;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = tokens::ReplyItem)]
+pub struct ReplyItem {
+    id: i64,
+    name: String,
+    scope: String,
+    created: i64,
+    used: Option<i64>,
+}
+
+impl From<TokenData> for ReplyItem {
+    fn from(value: TokenData) -> Self {
+        Self {
+            id: value.id(),
+            name: value.name().into(),
+            scope: value.scope().into(),
+            created: value.created(),
+            used: value.used(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(untagged)]
+#[schema(as = tokens::Reply)]
+pub enum Reply {
+    List {
+        success: bool,
+        items: Vec<ReplyItem>,
+    },
+    Created {
+        success: bool,
+        id: i64,
+        token: String,
+    },
+    Success {
+        success: bool,
+    },
+}
+
+impl Reply {
+    pub fn list(items: Vec<TokenData>) -> Self {
+        let items = items.into_iter().map(ReplyItem::from).collect();
+
+        Reply::List {
+            success: true,
+            items,
+        }
+    }
+
+    pub fn created(id: i64, token: String) -> Self {
+        Reply::Created {
+            success: true,
+            id,
+            token,
+        }
+    }
+
+    pub fn success() -> Self {
+        Reply::Success { success: true }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = tokens::CreateParams)]
+pub struct CreateParams {
+    name: String,
+    scope: String,
+}
+
+impl CreateParams {
+    pub fn name(&self) -> &str {
+        self.name.trim()
+    }
+
+    pub fn scope(&self) -> &str {
+        &self.scope
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(as = tokens::DeleteParams)]
+pub struct DeleteParams {
+    id: i64,
+}
+
+impl DeleteParams {
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+}
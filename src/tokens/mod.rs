@@ -0,0 +1,102 @@
+mod data;
+
+pub use self::data::CreateParams;
+pub use self::data::DeleteParams;
+pub use self::data::Reply;
+
+use crate::api_error::OrReject;
+use crate::database::Database;
+use crate::database::UserData;
+use crate::database::SCOPE_FULL;
+use crate::database::SCOPE_READ;
+use crate::database::SCOPE_SCAN;
+use crate::users;
+use receipt_check::Error;
+use warp::Rejection;
+
+/// Lists API tokens of the user.
+#[utoipa::path(
+    get,
+    path = "/api/v1/tokens",
+    tag = "tokens",
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn list(user: UserData, database: Database) -> Result<impl warp::Reply, Rejection> {
+    info!("Request API tokens: {}", user.login());
+
+    let items = database
+        .select_tokens(user.id())
+        .await
+        .or_reject("Failed to read tokens")?;
+
+    Ok(warp::reply::json(&Reply::list(items)))
+}
+
+/// Creates a token for the user, the token itself is returned only once and
+/// only its hash is stored.
+#[utoipa::path(
+    post,
+    path = "/api/v1/tokens",
+    tag = "tokens",
+    request_body = CreateParams,
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn create(
+    user: UserData,
+    params: CreateParams,
+    database: Database,
+) -> Result<impl warp::Reply, Rejection> {
+    info!("Request API token create: {:?}", params);
+
+    validate(params.name(), params.scope()).or_reject("Invalid token")?;
+
+    let token = users::new_token();
+    let id = database
+        .insert_token(
+            user.id(),
+            params.name(),
+            &users::hash_token(&token),
+            params.scope(),
+            users::now(),
+        )
+        .await
+        .or_reject("Failed to save token")?;
+
+    Ok(warp::reply::json(&Reply::created(id, token)))
+}
+
+/// Revokes an API token.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/tokens/{id}",
+    tag = "tokens",
+    params(
+        ("id" = i64, Path, description = "Token id"),
+    ),
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn delete(
+    user: UserData,
+    params: DeleteParams,
+    database: Database,
+) -> Result<impl warp::Reply, Rejection> {
+    info!("Request API token delete: {:?}", params);
+
+    database
+        .remove_token(user.id(), params.id())
+        .await
+        .or_reject("Failed to delete token")?;
+
+    Ok(warp::reply::json(&Reply::success()))
+}
+
+fn validate(name: &str, scope: &str) -> Result<(), Error> {
+    if name.is_empty() {
+        return Err(Error::Invalid("Token name is empty".into()));
+    }
+
+    match scope {
+        SCOPE_FULL | SCOPE_READ | SCOPE_SCAN => Ok(()),
+        _ => Err(Error::Invalid(format!("Unknown token scope: {}", scope))),
+    }
+}
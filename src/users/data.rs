@@ -0,0 +1,53 @@
+use crate::database::UserData;
+use serde::Deserialize;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(untagged)]
+#[schema(as = users::Reply)]
+pub enum Reply {
+    User {
+        success: bool,
+        id: i64,
+        login: String,
+        household: i64,
+        role: String,
+    },
+    Success {
+        success: bool,
+    },
+}
+
+impl Reply {
+    pub fn user(user: &UserData) -> Self {
+        Reply::User {
+            success: true,
+            id: user.id(),
+            login: user.login().into(),
+            household: user.household(),
+            role: user.role().into(),
+        }
+    }
+
+    pub fn success() -> Self {
+        Reply::Success { success: true }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[schema(as = users::LoginParams)]
+pub struct LoginParams {
+    login: String,
+    password: String,
+}
+
+impl LoginParams {
+    pub fn login(&self) -> &str {
+        self.login.trim()
+    }
+
+    pub fn password(&self) -> &str {
+        &self.password
+    }
+}
@@ -0,0 +1,496 @@
+mod data;
+
+pub use self::data::LoginParams;
+pub use self::data::Reply;
+
+use crate::api_error::ApiError;
+use crate::api_error::OrReject;
+use crate::database::Database;
+use crate::database::UserData;
+use crate::database::SCOPE_FULL;
+use crate::hex;
+use crate::proxy::Forwarded;
+use argon2::password_hash::PasswordHash;
+use argon2::password_hash::PasswordHasher;
+use argon2::password_hash::PasswordVerifier;
+use argon2::password_hash::SaltString;
+use argon2::Argon2;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::Digest;
+use sha2::Sha256;
+use std::error::Error;
+use time::OffsetDateTime;
+use warp::Filter;
+use warp::Rejection;
+
+pub const SESSION_COOKIE: &str = "session";
+const SESSION_LIFETIME: i64 = 30 * 24 * 60 * 60;
+const MIN_PASSWORD_LENGTH: usize = 8;
+/// Verified instead of a missing user's hash, so a login takes as long
+/// whether the user exists or not.
+const DUMMY_HASH: &str =
+    "$argon2id$v=19$m=19456,t=2,p=1$950kGb4Mvia4NosZVsIaKg$bdN+xAT0q2xHieValQyV5sUoYCvtd6qftOGe0Wp+mNM";
+
+/// Rejects requests without a valid session.
+fn unauthorized() -> Rejection {
+    warp::reject::custom(ApiError::Unauthorized("Unauthorized".into()))
+}
+
+/// Rejects requests of household members without the admin role or made
+/// with an API token without the required scope.
+fn forbidden(message: &str) -> Rejection {
+    warp::reject::custom(ApiError::Forbidden(message.into()))
+}
+
+/// Extracts the user of the bearer API token or of the session cookie,
+/// requests without valid credentials are rejected as unauthorized.
+/// Tokens need `scope` or the full scope, sessions are allowed everything.
+pub fn user(
+    database: Database,
+    scope: &'static str,
+) -> impl Filter<Extract = (UserData,), Error = Rejection> + Clone {
+    warp::header::optional::<String>("authorization")
+        .and(warp::cookie::optional(SESSION_COOKIE))
+        .and(warp::any().map(move || database.clone()))
+        .and_then(move |authorization, session, database| {
+            authenticate(authorization, session, database, scope)
+        })
+}
+
+/// Extracts the user of the session cookie like [`user`], users without the
+/// admin role are rejected as forbidden.
+pub fn admin(database: Database) -> impl Filter<Extract = (UserData,), Error = Rejection> + Clone {
+    user(database, SCOPE_FULL).and_then(|user: UserData| async move {
+        match user.is_admin() {
+            true => Ok(user),
+            false => Err(forbidden("Only household admins can do this")),
+        }
+    })
+}
+
+/// Registers a user with a new household and starts a session.
+#[utoipa::path(
+    post,
+    path = "/api/v1/users",
+    tag = "users",
+    request_body = LoginParams,
+    security(()),
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn register(
+    forwarded: Forwarded,
+    params: LoginParams,
+    database: Database,
+) -> Result<Box<dyn warp::Reply>, Rejection> {
+    info!("Request user register: {}", params.login());
+
+    validate(params.login(), params.password()).or_reject("Invalid user")?;
+
+    let password = hash_password(params.password()).or_reject("Failed to hash password")?;
+    let user = database
+        .insert_user(params.login(), &password)
+        .await
+        .or_reject("Failed to save user")?;
+
+    start_session(&database, &user, &forwarded).await
+}
+
+/// Starts a session and sets the session cookie.
+#[utoipa::path(
+    post,
+    path = "/api/v1/session",
+    tag = "users",
+    request_body = LoginParams,
+    security(()),
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn login(
+    forwarded: Forwarded,
+    params: LoginParams,
+    database: Database,
+) -> Result<Box<dyn warp::Reply>, Rejection> {
+    info!("Request user login: {}", params.login());
+
+    let user = database
+        .select_user_password(params.login())
+        .await
+        .or_reject("Failed to read user")?;
+
+    match user {
+        Some((user, password)) if verify_password(params.password(), &password) => {
+            start_session(&database, &user, &forwarded).await
+        }
+        user => {
+            if user.is_none() {
+                verify_password(params.password(), DUMMY_HASH);
+            }
+
+            warn!("Invalid login or password: {}", params.login());
+
+            Err(warp::reject::custom(ApiError::Unauthorized(
+                "Invalid login or password".into(),
+            )))
+        }
+    }
+}
+
+/// Ends the session and clears the session cookie.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/session",
+    tag = "users",
+    security(()),
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn logout(
+    forwarded: Forwarded,
+    token: Option<String>,
+    database: Database,
+) -> Result<Box<dyn warp::Reply>, Rejection> {
+    info!("Request user logout");
+
+    if let Some(token) = token {
+        database
+            .remove_session(&hash_token(&token), now())
+            .await
+            .or_reject("Failed to remove session")?;
+    }
+
+    let cookie = session_cookie("", 0, &forwarded);
+    let reply = warp::reply::json(&Reply::success());
+
+    Ok(Box::new(warp::reply::with_header(
+        reply,
+        "set-cookie",
+        cookie,
+    )))
+}
+
+/// Returns the current user.
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/me",
+    tag = "users",
+    responses((status = 200, description = "Success", body = Reply))
+)]
+pub async fn me(user: UserData) -> Result<impl warp::Reply, Rejection> {
+    info!("Request current user: {}", user.login());
+
+    Ok(warp::reply::json(&Reply::user(&user)))
+}
+
+async fn authenticate(
+    authorization: Option<String>,
+    session: Option<String>,
+    database: Database,
+    scope: &'static str,
+) -> Result<UserData, Rejection> {
+    let token = authorization
+        .as_deref()
+        .and_then(|authorization| authorization.strip_prefix("Bearer "));
+
+    if let Some(token) = token {
+        return match database
+            .select_token_user(&hash_token(token.trim()), now())
+            .await
+        {
+            Ok(Some((user, token_scope))) if token_scope == SCOPE_FULL || token_scope == scope => {
+                Ok(user)
+            }
+            Ok(Some(_)) => Err(forbidden("API token scope does not allow this")),
+            Ok(None) => Err(unauthorized()),
+            Err(error) => {
+                warn!("Failed to read token: {}", error);
+
+                Err(unauthorized())
+            }
+        };
+    }
+
+    let session = session.ok_or_else(unauthorized)?;
+
+    match database
+        .select_session_user(&hash_token(&session), now())
+        .await
+    {
+        Ok(Some(user)) => Ok(user),
+        Ok(None) => Err(unauthorized()),
+        Err(error) => {
+            warn!("Failed to read session: {}", error);
+
+            Err(unauthorized())
+        }
+    }
+}
+
+async fn start_session(
+    database: &Database,
+    user: &UserData,
+    forwarded: &Forwarded,
+) -> Result<Box<dyn warp::Reply>, Rejection> {
+    let token = new_token();
+
+    database
+        .insert_session(&hash_token(&token), user.id(), now() + SESSION_LIFETIME)
+        .await
+        .or_reject("Failed to save session")?;
+
+    let cookie = session_cookie(&token, SESSION_LIFETIME, forwarded);
+    let reply = warp::reply::json(&Reply::user(user));
+
+    Ok(Box::new(warp::reply::with_header(
+        reply,
+        "set-cookie",
+        cookie,
+    )))
+}
+
+/// Session cookies are marked secure unless the client connected over plain
+/// HTTP, which browsers only accept for local development.
+fn session_cookie(token: &str, max_age: i64, forwarded: &Forwarded) -> String {
+    let secure = if forwarded.is_secure() {
+        "; Secure"
+    } else {
+        ""
+    };
+
+    format!(
+        "{}={}; Path=/; HttpOnly{}; SameSite=Strict; Max-Age={}",
+        SESSION_COOKIE, token, secure, max_age
+    )
+}
+
+pub fn validate(login: &str, password: &str) -> Result<(), receipt_check::Error> {
+    if login.is_empty() {
+        return Err(receipt_check::Error::Invalid("Login is empty".into()));
+    }
+
+    if password.chars().count() < MIN_PASSWORD_LENGTH {
+        return Err(receipt_check::Error::Invalid(format!(
+            "Password must have at least {} characters",
+            MIN_PASSWORD_LENGTH
+        )));
+    }
+
+    Ok(())
+}
+
+pub fn hash_password(password: &str) -> Result<String, Box<dyn Error>> {
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = Argon2::default().hash_password(password.as_bytes(), &salt)?;
+
+    Ok(hash.to_string())
+}
+
+fn verify_password(password: &str, hash: &str) -> bool {
+    match PasswordHash::new(hash) {
+        Ok(hash) => Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .is_ok(),
+        Err(error) => {
+            warn!("Invalid password hash: {}", error);
+
+            false
+        }
+    }
+}
+
+pub fn new_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+
+    hex::encode(&bytes)
+}
+
+/// Sessions and API tokens are stored by token hash, so a leaked database
+/// does not expose usable tokens.
+pub fn hash_token(token: &str) -> String {
+    hex::encode(&Sha256::digest(token.as_bytes()))
+}
+
+pub fn now() -> i64 {
+    OffsetDateTime::now_utc().unix_timestamp()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::ROLE_MEMBER;
+    use crate::database::SCOPE_READ;
+    use crate::database::SCOPE_SCAN;
+
+    async fn database() -> (Database, UserData) {
+        let database = Database::new(":memory:").unwrap();
+        let user = database.insert_user("alice", DUMMY_HASH).await.unwrap();
+
+        (database, user)
+    }
+
+    async fn session(database: &Database, user: &UserData) -> String {
+        let token = new_token();
+        database
+            .insert_session(&hash_token(&token), user.id(), now() + SESSION_LIFETIME)
+            .await
+            .unwrap();
+
+        format!("{}={}", SESSION_COOKIE, token)
+    }
+
+    async fn bearer(database: &Database, user: &UserData, scope: &str) -> String {
+        let token = new_token();
+        database
+            .insert_token(user.id(), scope, &hash_token(&token), scope, now())
+            .await
+            .unwrap();
+
+        format!("Bearer {}", token)
+    }
+
+    async fn scope_allowed(database: &Database, authorization: &str, scope: &'static str) -> bool {
+        warp::test::request()
+            .header("authorization", authorization)
+            .filter(&user(database.clone(), scope))
+            .await
+            .is_ok()
+    }
+
+    fn is_unauthorized(rejection: &Rejection) -> bool {
+        matches!(rejection.find(), Some(ApiError::Unauthorized(_)))
+    }
+
+    fn is_forbidden(rejection: &Rejection) -> bool {
+        matches!(rejection.find(), Some(ApiError::Forbidden(_)))
+    }
+
+    #[test]
+    fn dummy_hash_is_verified() {
+        assert!(verify_password("dummy-password", DUMMY_HASH));
+        assert!(!verify_password("password1", DUMMY_HASH));
+    }
+
+    #[tokio::test]
+    async fn session_authenticates_user() {
+        let (database, alice) = database().await;
+        let cookie = session(&database, &alice).await;
+
+        let user = warp::test::request()
+            .header("cookie", cookie)
+            .filter(&user(database, SCOPE_READ))
+            .await
+            .unwrap();
+
+        assert_eq!(user.id(), alice.id());
+    }
+
+    #[tokio::test]
+    async fn expired_session_is_rejected() {
+        let (database, alice) = database().await;
+        let token = new_token();
+        database
+            .insert_session(&hash_token(&token), alice.id(), now() - 1)
+            .await
+            .unwrap();
+
+        let rejection = warp::test::request()
+            .header("cookie", format!("{}={}", SESSION_COOKIE, token))
+            .filter(&user(database, SCOPE_READ))
+            .await
+            .unwrap_err();
+
+        assert!(is_unauthorized(&rejection));
+    }
+
+    #[tokio::test]
+    async fn missing_session_is_rejected() {
+        let (database, _) = database().await;
+
+        let rejection = warp::test::request()
+            .filter(&user(database, SCOPE_READ))
+            .await
+            .unwrap_err();
+
+        assert!(is_unauthorized(&rejection));
+    }
+    #[tokio::test]
+    async fn admin_is_allowed() {
+        let (database, alice) = database().await;
+        let cookie = session(&database, &alice).await;
+
+        let user = warp::test::request()
+            .header("cookie", cookie)
+            .filter(&admin(database))
+            .await
+            .unwrap();
+
+        assert!(user.is_admin());
+    }
+
+    #[tokio::test]
+    async fn member_is_forbidden_admin_routes() {
+        let (database, alice) = database().await;
+        let bob = database
+            .insert_member(alice.household(), "bob", DUMMY_HASH, ROLE_MEMBER)
+            .await
+            .unwrap();
+        let cookie = session(&database, &bob).await;
+
+        let member = warp::test::request()
+            .header("cookie", &cookie)
+            .filter(&user(database.clone(), SCOPE_READ))
+            .await
+            .unwrap();
+        let rejection = warp::test::request()
+            .header("cookie", &cookie)
+            .filter(&admin(database))
+            .await
+            .unwrap_err();
+
+        assert_eq!(member.household(), alice.household());
+        assert!(is_forbidden(&rejection));
+    }
+    #[tokio::test]
+    async fn token_scopes_limit_routes() {
+        let (database, alice) = database().await;
+        let full = bearer(&database, &alice, SCOPE_FULL).await;
+        let read = bearer(&database, &alice, SCOPE_READ).await;
+        let scan = bearer(&database, &alice, SCOPE_SCAN).await;
+
+        assert!(scope_allowed(&database, &full, SCOPE_FULL).await);
+        assert!(scope_allowed(&database, &full, SCOPE_READ).await);
+        assert!(scope_allowed(&database, &full, SCOPE_SCAN).await);
+        assert!(scope_allowed(&database, &read, SCOPE_READ).await);
+        assert!(!scope_allowed(&database, &read, SCOPE_SCAN).await);
+        assert!(!scope_allowed(&database, &read, SCOPE_FULL).await);
+        assert!(scope_allowed(&database, &scan, SCOPE_SCAN).await);
+        assert!(!scope_allowed(&database, &scan, SCOPE_READ).await);
+        assert!(!scope_allowed(&database, &scan, SCOPE_FULL).await);
+    }
+
+    #[tokio::test]
+    async fn token_without_scope_is_forbidden() {
+        let (database, alice) = database().await;
+        let read = bearer(&database, &alice, SCOPE_READ).await;
+
+        let rejection = warp::test::request()
+            .header("authorization", read)
+            .filter(&admin(database))
+            .await
+            .unwrap_err();
+
+        assert!(is_forbidden(&rejection));
+    }
+
+    #[tokio::test]
+    async fn unknown_token_is_rejected() {
+        let (database, _) = database().await;
+
+        let rejection = warp::test::request()
+            .header("authorization", format!("Bearer {}", new_token()))
+            .filter(&user(database, SCOPE_READ))
+            .await
+            .unwrap_err();
+
+        assert!(is_unauthorized(&rejection));
+    }
+}
@@ -0,0 +1,400 @@
+use crate::budgets;
+use crate::categories;
+use crate::classifier::Classifier;
+use crate::database::Database;
+use crate::database::SCOPE_FULL;
+use crate::database::SCOPE_READ;
+use crate::database::SCOPE_SCAN;
+use crate::households;
+use crate::ofd::Provider;
+use crate::products;
+use crate::proxy;
+use crate::proxy::Proxies;
+use crate::qrcode;
+use crate::rest;
+use crate::rest::Segment;
+use crate::rules;
+use crate::splits;
+use crate::stores;
+use crate::tickets;
+use crate::tokens;
+use crate::users;
+use crate::with;
+use warp::filters::BoxedFilter;
+use warp::Filter;
+
+/// Routes of the versioned REST API under `/api/v1`. They share handlers
+/// with the deprecated `POST` routes, ids of resources are taken from the
+/// path and merged into the params of the handlers.
+pub fn routes(
+    database: &Database,
+    classifier: &Classifier,
+    provider: &Provider,
+    proxies: &Proxies,
+) -> BoxedFilter<(impl warp::Reply,)> {
+    let users_register = warp::path!("api" / "v1" / "users")
+        .and(warp::post())
+        .and(proxy::forwarded(proxies.clone()))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and_then(users::register);
+    let users_me = warp::path!("api" / "v1" / "users" / "me")
+        .and(warp::get())
+        .and(users::user(database.clone(), SCOPE_READ))
+        .and_then(users::me);
+    let session_login = warp::path!("api" / "v1" / "session")
+        .and(warp::post())
+        .and(proxy::forwarded(proxies.clone()))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and_then(users::login);
+    let session_logout = warp::path!("api" / "v1" / "session")
+        .and(warp::delete())
+        .and(proxy::forwarded(proxies.clone()))
+        .and(warp::cookie::optional(users::SESSION_COOKIE))
+        .and(with(database.clone()))
+        .and_then(users::logout);
+    let members_list = warp::path!("api" / "v1" / "household" / "members")
+        .and(warp::get())
+        .and(users::user(database.clone(), SCOPE_READ))
+        .and(with(database.clone()))
+        .and_then(households::members);
+    let members_create = warp::path!("api" / "v1" / "household" / "members")
+        .and(warp::post())
+        .and(users::admin(database.clone()))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and_then(households::create);
+    let members_role = warp::path!("api" / "v1" / "household" / "members" / i64 / "role")
+        .and(warp::put())
+        .and(users::admin(database.clone()))
+        .and(warp::body::json())
+        .and_then(rest::with_path::<i64, households::RoleParams>("id"))
+        .untuple_one()
+        .and(with(database.clone()))
+        .and_then(households::role);
+    let members_delete = warp::path!("api" / "v1" / "household" / "members" / i64)
+        .and(warp::delete())
+        .and(users::admin(database.clone()))
+        .and_then(rest::from_path::<i64, households::DeleteParams>("id"))
+        .untuple_one()
+        .and(with(database.clone()))
+        .and_then(households::delete);
+    let tokens_list = warp::path!("api" / "v1" / "tokens")
+        .and(warp::get())
+        .and(users::user(database.clone(), SCOPE_FULL))
+        .and(with(database.clone()))
+        .and_then(tokens::list);
+    let tokens_create = warp::path!("api" / "v1" / "tokens")
+        .and(warp::post())
+        .and(users::user(database.clone(), SCOPE_FULL))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and_then(tokens::create);
+    let tokens_delete = warp::path!("api" / "v1" / "tokens" / i64)
+        .and(warp::delete())
+        .and(users::user(database.clone(), SCOPE_FULL))
+        .and_then(rest::from_path::<i64, tokens::DeleteParams>("id"))
+        .untuple_one()
+        .and(with(database.clone()))
+        .and_then(tokens::delete);
+    let receipts_scan = warp::path!("api" / "v1" / "receipts")
+        .and(warp::post())
+        .and(users::user(database.clone(), SCOPE_SCAN))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and(with(provider.clone()))
+        .and_then(qrcode::qrcode);
+    let receipts_list = warp::path!("api" / "v1" / "receipts")
+        .and(warp::get())
+        .and(users::user(database.clone(), SCOPE_READ))
+        .and(with(database.clone()))
+        .and_then(tickets::list);
+    let receipts_clear = warp::path!("api" / "v1" / "receipts")
+        .and(warp::delete())
+        .and(users::admin(database.clone()))
+        .and(with(database.clone()))
+        .and_then(tickets::clear);
+    let receipts_get = warp::path!("api" / "v1" / "receipts" / Segment)
+        .map(String::from)
+        .and(warp::get())
+        .and(users::user(database.clone(), SCOPE_READ))
+        .and(with(database.clone()))
+        .and_then(tickets::receipt);
+    let receipts_delete = warp::path!("api" / "v1" / "receipts" / Segment)
+        .and(warp::delete())
+        .and(users::admin(database.clone()))
+        .and_then(rest::from_path::<Segment, tickets::DeleteParams>("ticket"))
+        .untuple_one()
+        .and(with(database.clone()))
+        .and_then(tickets::delete);
+    let products_list = warp::path!("api" / "v1" / "products")
+        .and(warp::get())
+        .and(users::user(database.clone(), SCOPE_READ))
+        .and(with(database.clone()))
+        .and_then(products::list);
+    let products_inflation = warp::path!("api" / "v1" / "products" / "inflation")
+        .and(warp::get())
+        .and(users::user(database.clone(), SCOPE_READ))
+        .and(with(database.clone()))
+        .and_then(products::inflation);
+    let products_prices = warp::path!("api" / "v1" / "products" / i64 / "prices")
+        .and(warp::get())
+        .and(users::user(database.clone(), SCOPE_READ))
+        .and(with(database.clone()))
+        .and_then(products::prices);
+    let products_category = warp::path!("api" / "v1" / "products" / Segment / "category")
+        .and(warp::put())
+        .and(users::admin(database.clone()))
+        .and(warp::body::json())
+        .and_then(rest::with_path::<Segment, categories::UpdateParams>(
+            "product",
+        ))
+        .untuple_one()
+        .and(with(database.clone()))
+        .and(with(classifier.clone()))
+        .and_then(categories::update);
+    let products_categories = warp::path!("api" / "v1" / "products" / "categories")
+        .and(warp::put())
+        .and(users::admin(database.clone()))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and(with(classifier.clone()))
+        .and_then(categories::assign);
+    let stores_compare = warp::path!("api" / "v1" / "stores" / "compare")
+        .and(warp::get())
+        .and(users::user(database.clone(), SCOPE_READ))
+        .and(with(database.clone()))
+        .and_then(stores::compare);
+    let budgets_list = warp::path!("api" / "v1" / "budgets")
+        .and(warp::get())
+        .and(users::user(database.clone(), SCOPE_READ))
+        .and(with(database.clone()))
+        .and_then(budgets::list);
+    let budgets_update = warp::path!("api" / "v1" / "budgets")
+        .and(warp::put())
+        .and(users::user(database.clone(), SCOPE_FULL))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and_then(budgets::update);
+    let budgets_delete = warp::path!("api" / "v1" / "budgets" / i64)
+        .and(warp::delete())
+        .and(users::user(database.clone(), SCOPE_FULL))
+        .and_then(rest::from_path::<i64, budgets::DeleteParams>("id"))
+        .untuple_one()
+        .and(with(database.clone()))
+        .and_then(budgets::delete);
+    let categories_list = warp::path!("api" / "v1" / "categories")
+        .and(warp::get())
+        .and(users::user(database.clone(), SCOPE_READ))
+        .and(with(database.clone()))
+        .and(with(classifier.clone()))
+        .and_then(categories::list);
+    let categories_create = warp::path!("api" / "v1" / "categories")
+        .and(warp::post())
+        .and(users::admin(database.clone()))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and_then(categories::create);
+    let categories_tree = warp::path!("api" / "v1" / "categories" / "tree")
+        .and(warp::get())
+        .and(users::user(database.clone(), SCOPE_READ))
+        .and(with(database.clone()))
+        .and_then(categories::tree);
+    let categories_rename = warp::path!("api" / "v1" / "categories" / i64 / "name")
+        .and(warp::put())
+        .and(users::admin(database.clone()))
+        .and(warp::body::json())
+        .and_then(rest::with_path::<i64, categories::RenameParams>("id"))
+        .untuple_one()
+        .and(with(database.clone()))
+        .and(with(classifier.clone()))
+        .and_then(categories::rename);
+    let categories_move = warp::path!("api" / "v1" / "categories" / i64 / "parent")
+        .and(warp::put())
+        .and(users::admin(database.clone()))
+        .and(warp::body::json())
+        .and_then(rest::with_path::<i64, categories::MoveParams>("id"))
+        .untuple_one()
+        .and(with(database.clone()))
+        .and(with(classifier.clone()))
+        .and_then(categories::move_node);
+    let categories_merge = warp::path!("api" / "v1" / "categories" / i64 / "merge")
+        .and(warp::post())
+        .and(users::admin(database.clone()))
+        .and(warp::body::json())
+        .and_then(rest::with_path::<i64, categories::MergeParams>("source"))
+        .untuple_one()
+        .and(with(database.clone()))
+        .and(with(classifier.clone()))
+        .and_then(categories::merge);
+    let categories_path_rename = warp::path!("api" / "v1" / "categories" / "paths" / "rename")
+        .and(warp::post())
+        .and(users::admin(database.clone()))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and(with(classifier.clone()))
+        .and_then(categories::rename_path);
+    let categories_path_merge = warp::path!("api" / "v1" / "categories" / "paths" / "merge")
+        .and(warp::post())
+        .and(users::admin(database.clone()))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and(with(classifier.clone()))
+        .and_then(categories::merge_path);
+    let categories_name_rename = warp::path!("api" / "v1" / "categories" / "names" / "rename")
+        .and(warp::post())
+        .and(users::admin(database.clone()))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and(with(classifier.clone()))
+        .and_then(categories::rename_name);
+    let people_list = warp::path!("api" / "v1" / "splits" / "people")
+        .and(warp::get())
+        .and(users::user(database.clone(), SCOPE_READ))
+        .and(with(database.clone()))
+        .and_then(splits::people);
+    let people_create = warp::path!("api" / "v1" / "splits" / "people")
+        .and(warp::post())
+        .and(users::user(database.clone(), SCOPE_FULL))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and_then(splits::create_person);
+    let people_delete = warp::path!("api" / "v1" / "splits" / "people" / i64)
+        .and(warp::delete())
+        .and(users::user(database.clone(), SCOPE_FULL))
+        .and_then(rest::from_path::<i64, splits::DeleteParams>("id"))
+        .untuple_one()
+        .and(with(database.clone()))
+        .and_then(splits::delete_person);
+    let splits_receipts = warp::path!("api" / "v1" / "splits" / "receipts")
+        .and(warp::get())
+        .and(users::user(database.clone(), SCOPE_READ))
+        .and(with(database.clone()))
+        .and_then(splits::receipts);
+    let splits_payer = warp::path!("api" / "v1" / "splits" / "receipts" / Segment / "payer")
+        .and(warp::put())
+        .and(users::user(database.clone(), SCOPE_FULL))
+        .and(warp::body::json())
+        .and_then(rest::with_path::<Segment, splits::PayerParams>("ticket"))
+        .untuple_one()
+        .and(with(database.clone()))
+        .and_then(splits::payer);
+    let splits_share = warp::path!("api" / "v1" / "splits" / "items" / i64 / "shares")
+        .and(warp::put())
+        .and(users::user(database.clone(), SCOPE_FULL))
+        .and(warp::body::json())
+        .and_then(rest::with_path::<i64, splits::ShareParams>("item"))
+        .untuple_one()
+        .and(with(database.clone()))
+        .and_then(splits::share);
+    let splits_balance = warp::path!("api" / "v1" / "splits" / "balance")
+        .and(warp::get())
+        .and(users::user(database.clone(), SCOPE_READ))
+        .and(with(database.clone()))
+        .and_then(splits::balance);
+    let rules_list = warp::path!("api" / "v1" / "rules")
+        .and(warp::get())
+        .and(users::user(database.clone(), SCOPE_READ))
+        .and(with(database.clone()))
+        .and_then(rules::list);
+    let rules_create = warp::path!("api" / "v1" / "rules")
+        .and(warp::post())
+        .and(users::admin(database.clone()))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and_then(rules::create);
+    let rules_update = warp::path!("api" / "v1" / "rules" / i64)
+        .and(warp::put())
+        .and(users::admin(database.clone()))
+        .and(warp::body::json())
+        .and_then(rest::with_path::<i64, rules::UpdateParams>("id"))
+        .untuple_one()
+        .and(with(database.clone()))
+        .and_then(rules::update);
+    let rules_delete = warp::path!("api" / "v1" / "rules" / i64)
+        .and(warp::delete())
+        .and(users::admin(database.clone()))
+        .and_then(rest::from_path::<i64, rules::DeleteParams>("id"))
+        .untuple_one()
+        .and(with(database.clone()))
+        .and_then(rules::delete);
+    let rules_preview = warp::path!("api" / "v1" / "rules" / "preview")
+        .and(warp::post())
+        .and(users::user(database.clone(), SCOPE_READ))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and_then(rules::preview);
+    let rules_apply = warp::path!("api" / "v1" / "rules" / "apply")
+        .and(warp::post())
+        .and(users::admin(database.clone()))
+        .and(warp::body::json())
+        .and(with(database.clone()))
+        .and(with(classifier.clone()))
+        .and_then(rules::apply);
+    let users = users_register
+        .or(users_me)
+        .or(session_login)
+        .or(session_logout)
+        .or(members_list)
+        .or(members_create)
+        .or(members_role)
+        .or(members_delete)
+        .or(tokens_list)
+        .or(tokens_create)
+        .or(tokens_delete)
+        .boxed();
+    let receipts = receipts_scan
+        .or(receipts_list)
+        .or(receipts_clear)
+        .or(receipts_get)
+        .or(receipts_delete)
+        .boxed();
+    let reports = products_list
+        .or(products_inflation)
+        .or(products_prices)
+        .or(products_category)
+        .or(products_categories)
+        .or(stores_compare)
+        .or(budgets_list)
+        .or(budgets_update)
+        .or(budgets_delete)
+        .boxed();
+    let categories = categories_list
+        .or(categories_create)
+        .or(categories_tree)
+        .or(categories_rename)
+        .or(categories_move)
+        .or(categories_merge)
+        .or(categories_path_rename)
+        .or(categories_path_merge)
+        .or(categories_name_rename)
+        .boxed();
+    let splits = people_list
+        .or(people_create)
+        .or(people_delete)
+        .or(splits_receipts)
+        .or(splits_payer)
+        .or(splits_share)
+        .or(splits_balance)
+        .boxed();
+    let rules = rules_list
+        .or(rules_create)
+        .or(rules_update)
+        .or(rules_delete)
+        .or(rules_preview)
+        .or(rules_apply)
+        .boxed();
+
+    users
+        .or(receipts)
+        .or(reports)
+        .or(categories)
+        .or(splits)
+        .or(rules)
+        .with(warp::reply::with::header(
+            "cache-control",
+            "private, no-cache",
+        ))
+        .boxed()
+}